@@ -0,0 +1,72 @@
+use bevy::{
+    math::{Vec3, primitives::Sphere},
+    prelude::{Mesh, Meshable},
+    render::mesh::VertexAttributeValues,
+    tasks::TaskPool,
+};
+use bevy_wallpaper::{
+    config::{QualityConfig, QualityPreset, SphereNoise},
+    quality::Quality,
+    sphere::{Undulation, loop_offset, undulate, undulate_parallel},
+};
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+
+/// the base positions of the sphere at `subdivisions`, the same as the wallpaper builds.
+fn base(subdivisions: u32) -> Vec<Vec3> {
+    let mesh = Sphere::default().mesh().ico(subdivisions).unwrap();
+
+    match mesh.attribute(Mesh::ATTRIBUTE_POSITION) {
+        Some(VertexAttributeValues::Float32x3(positions)) => {
+            positions.iter().copied().map(Vec3::from).collect()
+        }
+        _ => unreachable!("an ico sphere has positions"),
+    }
+}
+
+fn undulation(c: &mut Criterion) {
+    let presets = [
+        ("low", QualityPreset::Low),
+        ("medium", QualityPreset::Medium),
+        ("high", QualityPreset::High),
+        ("custom-max", QualityPreset::Custom),
+    ];
+    let custom = QualityConfig {
+        subdivisions: 79,
+        ..Default::default()
+    };
+    let undulation = Undulation {
+        seed: 7,
+        noise: SphereNoise::default(),
+        fading: None,
+    };
+    let offset = loop_offset(0.3, 60.0);
+    let pool = TaskPool::new();
+    let mut group = c.benchmark_group("undulate");
+
+    for (name, preset) in presets {
+        let base = base(Quality::of(preset, &custom).subdivisions);
+        let mut positions = vec![[0.0; 3]; base.len()];
+        let mut normals = vec![[0.0; 3]; base.len()];
+
+        group.bench_with_input(BenchmarkId::new("serial", name), &base, |b, base| {
+            b.iter(|| undulate(&undulation, offset, base, &mut positions, &mut normals));
+        });
+        group.bench_with_input(BenchmarkId::new("parallel", name), &base, |b, base| {
+            b.iter(|| {
+                undulate_parallel(
+                    &pool,
+                    &undulation,
+                    offset,
+                    base,
+                    &mut positions,
+                    &mut normals,
+                )
+            });
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, undulation);
+criterion_main!(benches);
@@ -1,3 +1,163 @@
-fn main() {
-    println!("todo");
+use bevy_wallpaper::{
+    Mode,
+    config::{NoiseChange, QualityPreset},
+    ipc::{self, Request, Response, Status},
+};
+use clap::{Parser, Subcommand, ValueEnum};
+use std::process::ExitCode;
+
+/// control a running bg-serv.
+#[derive(Parser, Debug)]
+#[command(version, about)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// switch the wallpaper to a different mode.
+    Mode { mode: Mode },
+    /// pause all animation.
+    Pause,
+    /// resume animation after a pause.
+    Resume,
+    /// show what bg-serv is currently doing.
+    Status {
+        /// print the raw status as JSON.
+        #[arg(long)]
+        json: bool,
+    },
+    /// recolor the wallpaper with a built in or configured palette, or `pywal`.
+    Palette { name: String },
+    /// switch to a quality preset, `custom` uses the settings from the config file.
+    Quality { preset: QualityPreset },
+    /// change the noise the sphere undulates with, fading over from the current one.
+    Noise {
+        #[command(flatten)]
+        change: NoiseChange,
+    },
+}
+
+impl From<&Command> for Request {
+    fn from(command: &Command) -> Self {
+        match command {
+            Command::Mode { mode } => Request::Mode(*mode),
+            Command::Pause => Request::Pause,
+            Command::Resume => Request::Resume,
+            Command::Status { .. } => Request::Status,
+            Command::Palette { name } => Request::Palette(name.clone()),
+            Command::Quality { preset } => Request::Quality(*preset),
+            Command::Noise { change } => Request::Noise(change.clone()),
+        }
+    }
+}
+
+/// what `value` is called on the command line.
+fn name(value: impl ValueEnum) -> String {
+    value
+        .to_possible_value()
+        .map(|value| value.get_name().to_string())
+        .unwrap_or_default()
+}
+
+fn print_status(status: &Status) {
+    let paused = if status.paused { " (paused)" } else { "" };
+    println!("mode:         {}{paused}", status.mode);
+    println!("palette:      {}", status.palette);
+    println!("quality:      {}", status.quality);
+
+    let noise = &status.noise;
+    println!(
+        "noise:        {}, {} x{}, frequency {}, amplitude {}, warp {}",
+        name(noise.kind),
+        name(noise.fractal),
+        noise.octaves,
+        noise.frequency,
+        noise.amplitude,
+        noise.warp
+    );
+
+    if status.windows.is_empty() {
+        println!("windows:      none");
+    }
+
+    for window in &status.windows {
+        let [width, height] = window.resolution;
+        let at = match window.position {
+            Some([x, y]) => format!(" at ({x}, {y})"),
+            None => String::new(),
+        };
+
+        println!(
+            "window:       {} {width}x{height}{at}, {}",
+            window.output, window.mode
+        );
+    }
+
+    match (status.frame_time_ms, status.fps) {
+        (Some(frame_time), Some(fps)) => {
+            println!("frame time:   {frame_time:.2} ms ({fps:.1} fps)")
+        }
+        _ => println!("frame time:   unknown"),
+    }
+
+    let space_things = status
+        .space_things
+        .iter()
+        .map(|(kind, count)| format!("{kind} {count}"))
+        .collect::<Vec<_>>();
+
+    if space_things.is_empty() {
+        println!("space things: none");
+    } else {
+        println!("space things: {}", space_things.join(", "));
+    }
+
+    if let Some(phase) = status.loop_phase {
+        println!("sphere loop:  {:.0}%", phase * 100.0);
+    }
+
+    let uptime = status.uptime_secs as u64;
+    println!(
+        "uptime:       {}h {:02}m {:02}s",
+        uptime / 3600,
+        uptime / 60 % 60,
+        uptime % 60
+    );
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    match ipc::send(&(&cli.command).into()) {
+        Ok(Response::Ok) => ExitCode::SUCCESS,
+        Ok(Response::Status(status)) => {
+            if let Command::Status { json: true } = cli.command {
+                match serde_json::to_string_pretty(&status) {
+                    Ok(json) => println!("{json}"),
+                    Err(e) => {
+                        eprintln!("failed to serialize status: {e}");
+                        return ExitCode::FAILURE;
+                    }
+                }
+            } else {
+                print_status(&status);
+            }
+
+            ExitCode::SUCCESS
+        }
+        Ok(Response::Error(e)) => {
+            eprintln!("bg-serv: {e}");
+            ExitCode::FAILURE
+        }
+        Err(e) => {
+            match ipc::socket_path() {
+                Ok(path) => eprintln!("failed to talk to bg-serv at {}: {e}", path.display()),
+                Err(_) => eprintln!("failed to talk to bg-serv: {e}"),
+            }
+
+            ExitCode::FAILURE
+        }
+    }
 }
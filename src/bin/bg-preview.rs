@@ -0,0 +1,137 @@
+use bevy::{
+    a11y::AccessibilityPlugin,
+    audio::AudioPlugin,
+    input::common_conditions::input_just_pressed,
+    log::{Level, LogPlugin},
+    pbr::wireframe::{WireframeConfig, WireframePlugin},
+    prelude::*,
+};
+use bevy_wallpaper::{
+    DebugTexture, EarlyLog, Mode,
+    clock::ClockPlugin,
+    config::{Config, ConfigPlugin, QualityPreset},
+    frame_rate::FrameRatePlugin,
+    log_early,
+    outputs::OutputsPlugin,
+    palette::{Palette, PalettePlugin},
+    quality::{QualityPlugin, SelectedQuality},
+    space_stuff::SpaceStuff,
+    sphere::SphereMode,
+    uv_debug_texture,
+};
+use clap::ValueEnum;
+use std::process;
+
+/// runs the wallpaper scenes in a normal window, for working on them without touching the
+/// desktop background.
+fn main() {
+    let config = Config::load().unwrap_or_else(|e| {
+        eprintln!("{e}");
+        process::exit(1);
+    });
+    let mut early_log = EarlyLog::default();
+    let palette = Palette::resolve(&config, &config.palette).unwrap_or_else(|e| {
+        early_log.warn(format!("{e}, falling back to the default palette"));
+        Palette::default()
+    });
+
+    App::new()
+        .add_plugins((
+            DefaultPlugins
+                .set(LogPlugin {
+                    level: Level::INFO,
+                    ..default()
+                })
+                .set(WindowPlugin {
+                    primary_window: Some(Window {
+                        title: "bg-preview".into(),
+                        resolution: (1280., 720.).into(),
+                        ..default()
+                    }),
+                    ..default()
+                })
+                .disable::<AccessibilityPlugin>()
+                .disable::<AudioPlugin>(),
+            WireframePlugin,
+            SpaceStuff,
+            SphereMode,
+            ConfigPlugin::default(),
+            PalettePlugin,
+            OutputsPlugin { from_config: false },
+            FrameRatePlugin,
+            ClockPlugin,
+            QualityPlugin,
+        ))
+        .init_state::<Mode>()
+        .insert_resource(WireframeConfig {
+            global: true,
+            default_color: palette.highlight.into(),
+        })
+        .insert_resource(ClearColor(palette.base.into()))
+        .insert_resource(palette)
+        .insert_resource(config)
+        .insert_resource(early_log)
+        .add_systems(Startup, (log_early, preview_setup, enter_startup_mode))
+        .add_systems(
+            Update,
+            (
+                cycle_mode.run_if(input_just_pressed(KeyCode::KeyM)),
+                toggle_pause.run_if(input_just_pressed(KeyCode::Space)),
+                toggle_wireframe.run_if(input_just_pressed(KeyCode::KeyW)),
+                cycle_quality.run_if(input_just_pressed(KeyCode::KeyQ)),
+            ),
+        )
+        .run();
+}
+
+fn preview_setup(
+    mut commands: Commands,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut images: ResMut<Assets<Image>>,
+) {
+    let debug_material = materials.add(StandardMaterial {
+        base_color_texture: Some(images.add(uv_debug_texture())),
+        ..default()
+    });
+
+    commands.spawn(DebugTexture(debug_material));
+
+    info!("m: next mode, space: pause, w: toggle wireframes, q: next quality preset");
+}
+
+fn enter_startup_mode(config: Res<Config>, mut next_mode: ResMut<NextState<Mode>>) {
+    next_mode.set(config.mode);
+}
+
+fn cycle_mode(mode: Res<State<Mode>>, mut next_mode: ResMut<NextState<Mode>>) {
+    let modes = Mode::value_variants();
+    let i = modes.iter().position(|m| m == mode.get()).unwrap_or(0);
+    let next = modes[(i + 1) % modes.len()];
+
+    info!("mode {next}");
+    next_mode.set(next);
+}
+
+fn cycle_quality(mut selected: ResMut<SelectedQuality>) {
+    let presets = QualityPreset::value_variants();
+    let i = presets.iter().position(|p| *p == **selected).unwrap_or(0);
+    let next = presets[(i + 1) % presets.len()];
+
+    info!("quality {next}");
+    **selected = next;
+}
+
+fn toggle_pause(mut time: ResMut<Time<Virtual>>) {
+    if time.is_paused() {
+        info!("resumed");
+        time.unpause();
+    } else {
+        info!("paused");
+        time.pause();
+    }
+}
+
+fn toggle_wireframe(mut wireframe: ResMut<WireframeConfig>) {
+    wireframe.global = !wireframe.global;
+    info!("wireframes {}", if wireframe.global { "on" } else { "off" });
+}
@@ -9,18 +9,157 @@ use bevy::{
         pipelined_rendering::PipelinedRenderingPlugin,
         render_resource::{Extent3d, TextureDimension, TextureFormat},
     },
-    window::{
-        PresentMode, WindowCreated, WindowLevel, WindowMode, WindowResized, WindowResolution,
-    },
+    window::WindowCreated,
     winit::{WakeUp, WinitPlugin},
 };
 use bevy_linux_wallpaper::WallpaperPlugin;
 use bevy_wallpaper::{
-    DebugTexture, Mode, Shape, space_stuff::SpaceStuff, sphere::SphereMode, uv_debug_texture,
+    DebugTexture, EarlyLog, Mode, Shape, WallpaperRng,
+    clock::ClockPlugin,
+    config::{Config, ConfigPlugin, Overrides, QualityPreset},
+    frame_rate::FrameRatePlugin,
+    ipc::IpcPlugin,
+    log_early,
+    outputs::{Monitors, OutputsPlugin, output_window},
+    palette::{Palette, PalettePlugin},
+    power::PowerPlugin,
+    quality::QualityPlugin,
+    space_stuff::SpaceStuff,
+    sphere::SphereMode,
+    uv_debug_texture,
 };
-use std::f32::consts::PI;
+use clap::{Parser, ValueEnum};
+use std::{env, f32::consts::PI, path::PathBuf, process, str::FromStr};
+
+const ENV_HELP: &str = "\
+Environment:
+  every option can also be set with a BG_ variable, e.g. BG_MODE=sphere or BG_LOG_LEVEL=debug.
+  these win over the options, which win over the config file.";
+
+/// draws an animated wallpaper behind the desktop.
+#[derive(Parser, Debug)]
+#[command(version, about, after_help = ENV_HELP)]
+struct Cli {
+    /// the mode to start in.
+    #[arg(long)]
+    mode: Option<Mode>,
+    /// the config file [default: $XDG_CONFIG_HOME/bevy_wallpaper/config.toml]
+    #[arg(long)]
+    config: Option<PathBuf>,
+    /// how much to log: error, warn, info, debug or trace [default: info]
+    #[arg(long)]
+    log_level: Option<Level>,
+    /// seed everything random with this, so every run looks the same.
+    #[arg(long)]
+    seed: Option<u64>,
+    /// the most frames to draw per second.
+    #[arg(long)]
+    fps: Option<f32>,
+    /// the palette to draw with.
+    #[arg(long)]
+    palette: Option<String>,
+    /// only draw on the output with this name.
+    #[arg(long)]
+    output: Option<String>,
+    /// how good the wallpaper looks, and how much drawing it costs.
+    #[arg(long)]
+    quality: Option<QualityPreset>,
+}
+
+impl Cli {
+    /// overwrites options with any `BG_` environment variables that are set.
+    fn with_env(self) -> Result<Self, String> {
+        self.with_vars(var)
+    }
+
+    /// overwrites options with the `BG_` variables `var` finds set.
+    fn with_vars(
+        mut self,
+        var: impl Fn(&str) -> Result<Option<String>, String>,
+    ) -> Result<Self, String> {
+        if let Some(mode) = var("BG_MODE")? {
+            self.mode = Some(Mode::from_str(&mode, true).map_err(|e| format!("BG_MODE: {e}"))?);
+        }
+
+        self.config = var("BG_CONFIG")?.map(PathBuf::from).or(self.config);
+        self.log_level = parse("BG_LOG_LEVEL", var("BG_LOG_LEVEL")?)?.or(self.log_level);
+        self.seed = parse("BG_SEED", var("BG_SEED")?)?.or(self.seed);
+        self.fps = parse("BG_FPS", var("BG_FPS")?)?.or(self.fps);
+        self.palette = var("BG_PALETTE")?.or(self.palette);
+        self.output = var("BG_OUTPUT")?.or(self.output);
+
+        if let Some(quality) = var("BG_QUALITY")? {
+            self.quality = Some(
+                QualityPreset::from_str(&quality, true).map_err(|e| format!("BG_QUALITY: {e}"))?,
+            );
+        }
+
+        Ok(self)
+    }
+
+    fn overrides(&self) -> Overrides {
+        Overrides {
+            mode: self.mode,
+            palette: self.palette.clone(),
+            output: self.output.clone(),
+            fps: self.fps,
+            seed: self.seed,
+            quality: self.quality,
+        }
+    }
+}
+
+/// an environment variable, `None` when it's unset or empty.
+fn var(name: &str) -> Result<Option<String>, String> {
+    match env::var(name) {
+        Ok(value) if value.is_empty() => Ok(None),
+        Ok(value) => Ok(Some(value)),
+        Err(env::VarError::NotPresent) => Ok(None),
+        Err(e) => Err(format!("{name}: {e}")),
+    }
+}
+
+/// the variable `name`'s `value`, parsed.
+fn parse<T: FromStr>(name: &str, value: Option<String>) -> Result<Option<T>, String>
+where
+    T::Err: std::fmt::Display,
+{
+    value
+        .map(|value| value.parse().map_err(|e| format!("{name}: {e}")))
+        .transpose()
+}
 
 fn main() {
+    // logging only starts with the app, so anything that stops bg-serv before then is printed,
+    // and anything else is kept in `early_log` until logging is up.
+    let cli = Cli::parse().with_env().unwrap_or_else(|e| {
+        eprintln!("{e}");
+        process::exit(2);
+    });
+    let config_path = cli.config.clone().unwrap_or_else(Config::path);
+    let overrides = cli.overrides();
+    let config = Config::load_from(&config_path)
+        .map_err(|e| e.to_string())
+        .and_then(|config| {
+            overrides
+                .apply(config)
+                .map_err(|e| format!("invalid options: {e}"))
+        })
+        .unwrap_or_else(|e| {
+            eprintln!("{e}");
+            process::exit(1);
+        });
+    let mut early_log = EarlyLog::default();
+    let palette = Palette::resolve(&config, &config.palette).unwrap_or_else(|e| {
+        early_log.warn(format!("{e}, falling back to the default palette"));
+        Palette::default()
+    });
+    let monitors = Monitors::detect(&mut early_log);
+    let outputs = config.outputs(monitors.0.as_deref()).unwrap_or_else(|e| {
+        eprintln!("{e}");
+        process::exit(1);
+    });
+    let primary_window = output_window(&outputs[0]);
     let mut wp_plug = WallpaperPlugin::<WakeUp>::default();
     wp_plug.run_on_any_thread = true;
 
@@ -28,25 +167,11 @@ fn main() {
         .add_plugins((
             DefaultPlugins
                 .set(LogPlugin {
-                    level: Level::INFO,
+                    level: cli.log_level.unwrap_or(Level::INFO),
                     ..default()
                 })
                 .set(WindowPlugin {
-                    primary_window: Some(Window {
-                        present_mode: PresentMode::AutoVsync,
-                        name: Some("wallpaper".into()),
-                        window_level: WindowLevel::AlwaysOnBottom,
-                        mode: WindowMode::Windowed,
-                        // mode: WindowMode::Fullscreen(MonitorSelection::Primary),
-                        skip_taskbar: false,
-                        titlebar_shown: false,
-                        // resolution: WindowResolution::new(1920., 1080.),
-                        resolution: WindowResolution::new(5760., 1080.),
-                        // position: WindowPosition::Centered(MonitorSelection::Primary),
-                        position: WindowPosition::At((0, 0).into()),
-                        fullsize_content_view: true,
-                        ..Default::default()
-                    }),
+                    primary_window: Some(primary_window),
                     ..Default::default()
                 })
                 // .disable::<PipelinedRenderingPlugin>()
@@ -57,6 +182,17 @@ fn main() {
             // ObjPlugin,
             SpaceStuff,
             SphereMode,
+            IpcPlugin,
+            ConfigPlugin {
+                path: config_path,
+                overrides,
+            },
+            PalettePlugin,
+            OutputsPlugin::default(),
+            FrameRatePlugin,
+            PowerPlugin,
+            ClockPlugin,
+            QualityPlugin,
             wp_plug,
         ))
         .init_state::<Mode>()
@@ -67,34 +203,24 @@ fn main() {
             global: true,
             // Controls the default color of all wireframes. Used as the default color for global wireframes.
             // Can be changed per mesh using the `WireframeColor` component.
-            default_color: Srgba {
-                red: (166. / 255.),
-                green: (227. / 255.),
-                blue: (161. / 255.),
-                alpha: 1.0,
-            }
-            .into(),
+            default_color: palette.highlight.into(),
         })
-        .insert_resource(ClearColor(
-            Srgba {
-                red: (30. / 255.),
-                green: (30. / 255.),
-                blue: (46. / 255.),
-                alpha: 1.0,
-            }
-            .into(),
-        ))
+        .insert_resource(ClearColor(palette.base.into()))
+        .insert_resource(palette)
+        .insert_resource(WallpaperRng::new(config.seed))
+        .insert_resource(config)
+        .insert_resource(monitors)
+        .insert_resource(early_log)
+        .add_systems(Startup, log_early)
         // .add_systems(Startup, (camera_setup, spawn_spacething).chain())
         .add_systems(Startup, camera_setup)
         // .add_systems(Startup, ())
-        .add_systems(Startup, enter_sphere_mode)
+        .add_systems(Startup, enter_startup_mode)
         // .add_systems(
         //     Update,
         //     (
         //         mod_spacething_transform,
-        //         log_window_resize,
         //         window_creation_log,
-        //         log_window_move,
         //         despawn_spacethings,
         //         spawn_spacething.run_if(time_to_spawn),
         //         // log_assets,
@@ -215,22 +341,8 @@ fn camera_setup(
 //     }
 // }
 
-fn enter_sphere_mode(mut next_mode: ResMut<NextState<Mode>>) {
-    next_mode.set(Mode::Sphere);
-}
-
-fn log_window_resize(mut resize_reader: EventReader<WindowResized>) {
-    for e in resize_reader.read() {
-        // When resolution is being changed
-        info!("size {:.1} x {:.1}", e.width, e.height);
-    }
-}
-
-fn log_window_move(mut resize_reader: EventReader<WindowMoved>) {
-    for e in resize_reader.read() {
-        // When resolution is being changed
-        info!("location {} x {}", e.position[0], e.position[1]);
-    }
+fn enter_startup_mode(config: Res<Config>, mut next_mode: ResMut<NextState<Mode>>) {
+    next_mode.set(config.mode);
 }
 
 fn window_creation_log(mut created_evs: EventReader<WindowCreated>) {
@@ -294,3 +406,33 @@ fn window_creation_log(mut created_evs: EventReader<WindowCreated>) {
 //         // }
 //     }
 // }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn variables_win_over_options() {
+        let cli =
+            Cli::try_parse_from(["bg-serv", "--mode", "sphere", "--fps", "60", "--seed", "1"])
+                .unwrap()
+                .with_vars(|name| {
+                    Ok(match name {
+                        "BG_MODE" => Some("space-stuff".into()),
+                        "BG_FPS" => Some("24".into()),
+                        _ => None,
+                    })
+                })
+                .unwrap();
+
+        assert_eq!(cli.mode, Some(Mode::SpaceStuff));
+        assert_eq!(cli.fps, Some(24.0));
+        assert_eq!(cli.seed, Some(1));
+
+        let bad = Cli::try_parse_from(["bg-serv"])
+            .unwrap()
+            .with_vars(|name| Ok((name == "BG_FPS").then(|| "fast".into())));
+
+        assert_eq!(bad.unwrap_err(), "BG_FPS: invalid float literal");
+    }
+}
@@ -0,0 +1,124 @@
+use crate::config::Config;
+use bevy::{prelude::*, time::TimeSystem};
+use std::time::Duration;
+
+/// sent on the first frame after a gap of at least `Config::time.resume_gap`, which is what a
+/// suspend looks like from in here.
+#[derive(Event, Clone, Copy, Debug, PartialEq)]
+pub struct Resumed {
+    /// how long nothing was drawn for.
+    pub gap: Duration,
+}
+
+/// keeps long frames from making animations jump: virtual time moves forward by no more than
+/// `Config::time.max_delta` per frame, and `Resumed` is sent after a suspend.
+pub struct ClockPlugin;
+
+impl Plugin for ClockPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Config>()
+            .add_event::<Resumed>()
+            .add_systems(First, detect_resume.after(TimeSystem))
+            .add_systems(Update, apply_max_delta.run_if(resource_changed::<Config>));
+    }
+}
+
+/// whether a frame taking `real_delta` means the machine was suspended in between.
+pub fn resumed(config: &Config, real_delta: Duration) -> bool {
+    real_delta.as_secs_f32() >= config.time.resume_gap
+}
+
+fn detect_resume(config: Res<Config>, real: Res<Time<Real>>, mut resumes: EventWriter<Resumed>) {
+    if resumed(&config, real.delta()) {
+        info!("resumed after {:.1}s", real.delta_secs());
+        resumes.send(Resumed { gap: real.delta() });
+    }
+}
+
+fn apply_max_delta(config: Res<Config>, mut time: ResMut<Time<Virtual>>) {
+    let max_delta = Duration::from_secs_f32(config.time.max_delta);
+
+    if time.max_delta() != max_delta {
+        debug!("frames move time forward by at most {max_delta:?}");
+        time.set_max_delta(max_delta);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        space_stuff::space_objects::{
+            SpaceThing, SpaceThingTrait, catalog::ThingKind, cataloged::Cataloged,
+        },
+        sphere::{LoopPhase, advance_phase},
+    };
+    use bevy::time::TimeUpdateStrategy;
+    use rand::{SeedableRng, rngs::StdRng};
+
+    fn move_things(mut things: Query<(&mut SpaceThing, &mut Transform)>, time: Res<Time>) {
+        for (mut thing, mut transform) in &mut things {
+            thing.update_location(&time, &mut transform);
+        }
+    }
+
+    fn frame(app: &mut App, delta: Duration) -> (Vec3, f32) {
+        let thing = app
+            .world_mut()
+            .query::<&Transform>()
+            .single(app.world())
+            .translation;
+        let phase = app.world().resource::<LoopPhase>().0;
+
+        app.insert_resource(TimeUpdateStrategy::ManualDuration(delta));
+        app.update();
+
+        let moved = app
+            .world_mut()
+            .query::<&Transform>()
+            .single(app.world())
+            .translation;
+
+        (moved - thing, app.world().resource::<LoopPhase>().0 - phase)
+    }
+
+    #[test]
+    fn an_hour_long_frame_moves_things_no_further_than_max_delta() {
+        let config = Config::default();
+        let max_delta = Duration::from_secs_f32(config.time.max_delta);
+        let mut thing = Cataloged::new(
+            "asteroid",
+            &ThingKind::default(),
+            &mut StdRng::seed_from_u64(7),
+        );
+        let transform = thing.get_transform(100.0);
+        let mut app = App::new();
+
+        app.add_plugins((MinimalPlugins, ClockPlugin))
+            .insert_resource(config)
+            .init_resource::<LoopPhase>()
+            .add_systems(Update, (advance_phase, move_things));
+        app.world_mut()
+            .spawn((SpaceThing::Cataloged(thing), transform));
+
+        // the very first frame has no delta.
+        app.update();
+
+        let (normal_step, normal_phase) = frame(&mut app, max_delta);
+        assert!(app.world().resource::<Events<Resumed>>().is_empty());
+
+        let (long_step, long_phase) = frame(&mut app, Duration::from_secs(3600));
+
+        assert_eq!(app.world().resource::<Time<Virtual>>().delta(), max_delta);
+        assert!(long_step.abs_diff_eq(normal_step, 1e-4));
+        assert!((long_phase - normal_phase).abs() < 1e-6);
+
+        let resumes = app.world().resource::<Events<Resumed>>();
+        let gaps = resumes
+            .get_cursor()
+            .read(resumes)
+            .map(|r| r.gap)
+            .collect::<Vec<_>>();
+        assert_eq!(gaps, [Duration::from_secs(3600)]);
+    }
+}
@@ -0,0 +1,1252 @@
+use crate::{
+    Mode,
+    palette::{BUILT_IN, Palette, pywal::PYWAL},
+};
+use bevy::prelude::*;
+use clap::{Args, ValueEnum};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::{
+    collections::BTreeMap,
+    env, fmt, fs, io,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+
+/// bg-serv settings, read from `$XDG_CONFIG_HOME/bevy_wallpaper/config.toml`.
+///
+/// every field is optional, anything left out falls back to the defaults documented below.
+#[derive(Clone, Debug, PartialEq, Resource, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// the mode bg-serv starts in. default: `"sphere"`
+    pub mode: Mode,
+    /// the palette to draw with, one of the catppuccin flavors (`"latte"`, `"frappe"`,
+    /// `"macchiato"`, `"mocha"`) or a name from `palettes`. default: `"mocha"`
+    pub palette: String,
+    /// user defined palettes, by name. roles left out fall back to mocha.
+    pub palettes: BTreeMap<String, Palette>,
+    /// deprecated, set the palette's `base` and `highlight` instead.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub colors: Option<ColorConfig>,
+    pub pywal: PywalConfig,
+    /// a single wallpaper window, used when no `outputs` are configured.
+    /// default: none, one window per monitor found through RandR
+    pub window: Option<WindowConfig>,
+    /// one wallpaper window per monitor. default: none, see `window`
+    pub outputs: Vec<OutputConfig>,
+    /// draw one scene across all outputs showing the same mode, lined up across the bezels using
+    /// each output's `physical` geometry. default: `false`
+    pub span: bool,
+    /// only draw on the output with this name. default: all of them
+    pub output: Option<String>,
+    /// the most frames to draw per second. default: none, as many as vsync allows
+    pub fps: Option<f32>,
+    /// only draw as many frames as the modes on screen need to look smooth. default: `false`
+    pub low_power: bool,
+    /// seeds everything random, so every run looks the same. read at startup.
+    /// default: a new seed every run
+    pub seed: Option<u64>,
+    pub space_stuff: SpaceStuffConfig,
+    pub sphere: SphereConfig,
+    pub power: PowerConfig,
+    pub time: TimeConfig,
+    pub quality: QualityConfig,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            mode: Mode::Sphere,
+            palette: "mocha".into(),
+            palettes: BTreeMap::new(),
+            colors: None,
+            pywal: PywalConfig::default(),
+            window: None,
+            outputs: Vec::new(),
+            span: false,
+            output: None,
+            fps: None,
+            low_power: false,
+            seed: None,
+            space_stuff: SpaceStuffConfig::default(),
+            sphere: SphereConfig::default(),
+            power: PowerConfig::default(),
+            time: TimeConfig::default(),
+            quality: QualityConfig::default(),
+        }
+    }
+}
+
+/// the colors from before palettes. each one set replaces a role of whichever palette is in use.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ColorConfig {
+    /// the palette's `base`.
+    pub clear: Option<HexColor>,
+    /// the palette's `highlight`.
+    pub wireframe: Option<HexColor>,
+}
+
+/// where the `"pywal"` palette reads its colors from.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PywalConfig {
+    /// a pywal/wallust `colors.json` or Xresources style color file.
+    /// default: `$XDG_CACHE_HOME/wal/colors.json`
+    pub path: Option<PathBuf>,
+}
+
+impl PywalConfig {
+    /// the configured path, or pywal's default output file.
+    pub fn path(&self) -> PathBuf {
+        self.path.clone().unwrap_or_else(|| {
+            env::var_os("XDG_CACHE_HOME")
+                .map(PathBuf::from)
+                .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".cache")))
+                .unwrap_or_default()
+                .join("wal")
+                .join("colors.json")
+        })
+    }
+}
+
+/// geometry of the wallpaper window, in pixels.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct WindowConfig {
+    /// default: `5760` (three 1920x1080 monitors side by side)
+    pub width: f32,
+    /// default: `1080`
+    pub height: f32,
+    /// left edge of the window. default: `0`
+    pub x: i32,
+    /// top edge of the window. default: `0`
+    pub y: i32,
+}
+
+impl Default for WindowConfig {
+    fn default() -> Self {
+        Self {
+            width: 5760.,
+            height: 1080.,
+            x: 0,
+            y: 0,
+        }
+    }
+}
+
+/// what to do while the wallpaper isn't worth the power it takes to draw.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PowerPolicy {
+    /// keep drawing as usual.
+    Run,
+    /// stop all animation.
+    Pause,
+    /// draw at most `power.reduced_fps` frames per second.
+    ReduceFps,
+    /// show `power.cheap_mode` instead.
+    SwitchMode,
+}
+
+/// how to save power on battery, or while a fullscreen window hides the wallpaper.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PowerConfig {
+    /// what to do while running on battery. default: `"reduce-fps"`
+    pub on_battery: PowerPolicy,
+    /// what to do while fullscreen windows cover every output. default: `"pause"`
+    pub covered: PowerPolicy,
+    /// the frame rate for `"reduce-fps"`. default: `10`
+    pub reduced_fps: f32,
+    /// the mode for `"switch-mode"`. default: `"space-stuff"`
+    pub cheap_mode: Mode,
+    /// where to look for batteries and chargers, read at startup.
+    /// default: `"/sys/class/power_supply"`
+    pub supply_path: PathBuf,
+}
+
+impl Default for PowerConfig {
+    fn default() -> Self {
+        Self {
+            on_battery: PowerPolicy::ReduceFps,
+            covered: PowerPolicy::Pause,
+            reduced_fps: 10.0,
+            cheap_mode: Mode::SpaceStuff,
+            supply_path: "/sys/class/power_supply".into(),
+        }
+    }
+}
+
+/// how animation time copes with long frames, like the first one after a suspend.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TimeConfig {
+    /// the most seconds a single frame moves animations forward by. tighter than bevy's own
+    /// quarter second, so a stall shows as a short slowdown instead of a skip. default: `0.1`
+    pub max_delta: f32,
+    /// a frame taking this many seconds or more counts as resuming from a suspend.
+    /// default: `5`
+    pub resume_gap: f32,
+}
+
+impl Default for TimeConfig {
+    fn default() -> Self {
+        Self {
+            max_delta: 0.1,
+            resume_gap: 5.0,
+        }
+    }
+}
+
+/// how good the wallpaper looks, and how much drawing it costs.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum QualityPreset {
+    /// a coarse sphere, no shadows and no anti-aliasing.
+    Low,
+    #[default]
+    Medium,
+    /// a finely divided sphere and sharper shadows.
+    High,
+    /// the settings given in `[quality]`.
+    Custom,
+}
+
+impl fmt::Display for QualityPreset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.to_possible_value() {
+            Some(value) => f.write_str(value.get_name()),
+            None => write!(f, "{self:?}"),
+        }
+    }
+}
+
+/// how colors are mapped from the lit scene to the screen.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Tonemap {
+    None,
+    Reinhard,
+    AcesFitted,
+    Agx,
+    TonyMcMapface,
+    BlenderFilmic,
+}
+
+/// the quality preset, and the settings used by the `"custom"` one.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct QualityConfig {
+    /// `"low"`, `"medium"`, `"high"` or `"custom"`. default: `"medium"`
+    pub preset: QualityPreset,
+    /// how many times the sphere's icosahedron is subdivided, up to 79, past which bevy won't
+    /// build it. default: `20`
+    pub subdivisions: u32,
+    /// whether lights cast shadows. default: `true`
+    pub shadows: bool,
+    /// the size of each side of a light's shadow map, in pixels. default: `1024`
+    pub shadow_map_size: usize,
+    /// samples per pixel for anti-aliasing, `1` (off), `2`, `4` or `8`. default: `4`
+    pub msaa: u32,
+    /// default: `"tony-mc-mapface"`
+    pub tonemapping: Tonemap,
+}
+
+impl Default for QualityConfig {
+    fn default() -> Self {
+        Self {
+            preset: QualityPreset::Medium,
+            subdivisions: 20,
+            shadows: true,
+            shadow_map_size: 1024,
+            msaa: 4,
+            tonemapping: Tonemap::TonyMcMapface,
+        }
+    }
+}
+
+/// settings for `Mode::SpaceStuff`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SpaceStuffConfig {
+    /// seconds between spawning new space things, on average. default: `2.5`
+    pub spawn_interval: f32,
+    /// how spawns are spread over time. default: `SpawnSchedule::Interval`
+    pub schedule: SpawnSchedule,
+    /// how many things spawn together with `SpawnSchedule::Burst`. default: `3`
+    pub burst_size: u32,
+    /// how many things should be on screen. spawning holds off while there are this many, and
+    /// goes twice as fast while there are fewer than half. unset to spawn regardless.
+    pub target_alive: Option<u32>,
+    /// things are despawned once they come this close to a camera. default: `0.5`
+    pub despawn_near: f32,
+    /// seconds things shrink away for when despawned in view, `0` to remove them at once.
+    /// default: `0.5`
+    pub despawn_fade: f32,
+    /// the catalog of space things to spawn, relative to the assets directory.
+    /// default: `"space_stuff.catalog.toml"`
+    pub catalog: String,
+    /// changes to how the catalog's kinds spawn, by name.
+    pub kinds: BTreeMap<String, SpawnOverride>,
+    /// the fewest frames per second space stuff draws in low power mode, however slowly things
+    /// are moving. default: `5`
+    pub low_power_fps: f32,
+}
+
+/// when space things spawn, all keep to `spawn_interval` on average.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SpawnSchedule {
+    /// exactly every `spawn_interval` seconds.
+    Interval,
+    /// at random, like raindrops: sometimes close together, sometimes long apart.
+    Poisson,
+    /// `burst_size` at a time, with `burst_size` times as long between them.
+    Burst,
+}
+
+/// replaces a catalog entry's spawn settings, anything left out keeps the catalog's.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SpawnOverride {
+    pub weight: Option<f32>,
+    pub max_alive: Option<u32>,
+    pub one_in: Option<u32>,
+}
+
+impl Default for SpaceStuffConfig {
+    fn default() -> Self {
+        Self {
+            spawn_interval: 2.5,
+            schedule: SpawnSchedule::Interval,
+            burst_size: 3,
+            target_alive: None,
+            despawn_near: 0.5,
+            despawn_fade: 0.5,
+            catalog: "space_stuff.catalog.toml".into(),
+            kinds: BTreeMap::new(),
+            low_power_fps: 5.0,
+        }
+    }
+}
+
+/// settings for `Mode::Sphere`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SphereConfig {
+    /// seconds before the surface's motion repeats itself. default: `60`
+    pub period: f32,
+    /// how often the surface is worked out. default: `"every-frame"`
+    pub update: SphereUpdate,
+    /// times per second the surface is worked out for `"interpolated"` and `"stepped"`.
+    /// default: `2`
+    pub update_rate: f32,
+    pub noise: SphereNoise,
+    /// deprecated, the palette's `accent`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub color: Option<HexColor>,
+    /// deprecated, the palette's `overlay`. its alpha is ignored.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bounding_color: Option<HexColor>,
+    /// deprecated, the palette's `mantle`. its alpha is ignored.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bounding_wireframe: Option<HexColor>,
+}
+
+impl Default for SphereConfig {
+    fn default() -> Self {
+        Self {
+            period: 60.0,
+            update: SphereUpdate::EveryFrame,
+            update_rate: 2.0,
+            noise: SphereNoise::default(),
+            color: None,
+            bounding_color: None,
+            bounding_wireframe: None,
+        }
+    }
+}
+
+/// how often the sphere's surface is worked out.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SphereUpdate {
+    /// on every frame, the smoothest and the most work.
+    EveryFrame,
+    /// `update_rate` times per second, blending from one to the next in between.
+    Interpolated,
+    /// `update_rate` times per second, holding still in between for a choppy look.
+    Stepped,
+}
+
+/// the noise every fractal octave is made of.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum NoiseKind {
+    Perlin,
+    OpenSimplex,
+    Simplex,
+    Value,
+    /// cells around scattered points.
+    Worley,
+}
+
+/// how octaves of noise are layered on top of each other.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum Fractal {
+    /// a single octave.
+    None,
+    /// fractal brownian motion, smaller bumps on top of bigger ones.
+    Fbm,
+    /// sharp ridges.
+    Ridged,
+    /// puffy, cloud like bumps.
+    Billow,
+}
+
+/// the noise that moves the sphere's surface.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SphereNoise {
+    /// default: `"perlin"`
+    pub kind: NoiseKind,
+    /// default: `"none"`
+    pub fractal: Fractal,
+    /// how many octaves a fractal has, up to 32. default: `4`
+    pub octaves: usize,
+    /// how many bumps fit across the sphere, roughly. default: `1.5`
+    pub frequency: f32,
+    /// how much the frequency grows from one octave to the next. default: `2`
+    pub lacunarity: f32,
+    /// how much each octave counts compared to the last. default: `0.5`
+    pub persistence: f32,
+    /// how far the surface moves in or out, as a fraction of the radius. default: `0.25`
+    pub amplitude: f32,
+    /// how strongly the noise is warped by more noise, `0` for not at all. default: `0`
+    pub warp: f32,
+}
+
+impl Default for SphereNoise {
+    fn default() -> Self {
+        Self {
+            kind: NoiseKind::Perlin,
+            fractal: Fractal::None,
+            octaves: 4,
+            frequency: 1.5,
+            lacunarity: 2.0,
+            persistence: 0.5,
+            amplitude: 0.25,
+            warp: 0.0,
+        }
+    }
+}
+
+impl SphereNoise {
+    pub fn validate(&self) -> Result<(), String> {
+        if !(1..=32).contains(&self.octaves) {
+            return Err(format!(
+                "sphere.noise.octaves must be from 1 to 32, got {}",
+                self.octaves
+            ));
+        }
+
+        for (name, value) in [
+            ("frequency", self.frequency),
+            ("lacunarity", self.lacunarity),
+        ] {
+            if !value.is_finite() || value <= 0.0 {
+                return Err(format!(
+                    "sphere.noise.{name} must be greater than 0, got {value}"
+                ));
+            }
+        }
+
+        for (name, value) in [
+            ("persistence", self.persistence),
+            ("amplitude", self.amplitude),
+            ("warp", self.warp),
+        ] {
+            if !value.is_finite() || value < 0.0 {
+                return Err(format!(
+                    "sphere.noise.{name} can't be negative, got {value}"
+                ));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// changes to the sphere's noise from bg-cli, anything left out stays as it is.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize, Args)]
+pub struct NoiseChange {
+    #[arg(long)]
+    pub kind: Option<NoiseKind>,
+    #[arg(long)]
+    pub fractal: Option<Fractal>,
+    #[arg(long)]
+    pub octaves: Option<usize>,
+    #[arg(long)]
+    pub frequency: Option<f32>,
+    #[arg(long)]
+    pub lacunarity: Option<f32>,
+    #[arg(long)]
+    pub persistence: Option<f32>,
+    #[arg(long)]
+    pub amplitude: Option<f32>,
+    #[arg(long)]
+    pub warp: Option<f32>,
+}
+
+impl NoiseChange {
+    /// `noise` with the changes made, if they leave it valid.
+    pub fn apply(&self, noise: &SphereNoise) -> Result<SphereNoise, String> {
+        let changed = SphereNoise {
+            kind: self.kind.unwrap_or(noise.kind),
+            fractal: self.fractal.unwrap_or(noise.fractal),
+            octaves: self.octaves.unwrap_or(noise.octaves),
+            frequency: self.frequency.unwrap_or(noise.frequency),
+            lacunarity: self.lacunarity.unwrap_or(noise.lacunarity),
+            persistence: self.persistence.unwrap_or(noise.persistence),
+            amplitude: self.amplitude.unwrap_or(noise.amplitude),
+            warp: self.warp.unwrap_or(noise.warp),
+        };
+
+        changed.validate()?;
+
+        Ok(changed)
+    }
+}
+
+/// a monitor to draw the wallpaper on, with its own window and camera.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct OutputConfig {
+    /// a name to tell outputs apart, e.g. the monitor's connector `"DP-1"`.
+    pub name: String,
+    /// in physical pixels, whatever the scale.
+    pub width: f32,
+    pub height: f32,
+    pub x: i32,
+    pub y: i32,
+    /// the mode shown on this output. default: follow the global mode
+    #[serde(default)]
+    pub mode: Option<Mode>,
+    /// degrees to turn this output's camera around the scene. default: `0`
+    #[serde(default)]
+    pub yaw: f32,
+    /// the window's scale factor. default: whatever the window system picks
+    #[serde(default)]
+    pub scale: Option<f32>,
+    /// where the screen sits on the wall, needed for `span`.
+    #[serde(default)]
+    pub physical: Option<PhysicalConfig>,
+}
+
+/// the visible area of a screen in millimeters, measured from the same corner for every screen,
+/// so the gaps between them are the bezels.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PhysicalConfig {
+    /// left edge.
+    pub x: f32,
+    /// top edge.
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+/// a color that (de)serializes as a hex string.
+#[derive(Clone, Copy, Debug, PartialEq, Deref, DerefMut)]
+pub struct HexColor(pub Srgba);
+
+impl From<HexColor> for Color {
+    fn from(color: HexColor) -> Self {
+        color.0.into()
+    }
+}
+
+impl Serialize for HexColor {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.0.to_hex())
+    }
+}
+
+impl<'de> Deserialize<'de> for HexColor {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let hex = String::deserialize(deserializer)?;
+
+        Srgba::hex(&hex).map(HexColor).map_err(|_| {
+            serde::de::Error::custom(format!(
+                "invalid color `{hex}`, expected a hex color like \"#1e1e2e\" or \"#1e1e2eff\""
+            ))
+        })
+    }
+}
+
+/// why the config file couldn't be used.
+#[derive(Debug)]
+pub enum ConfigError {
+    Io {
+        path: PathBuf,
+        source: io::Error,
+    },
+    Parse {
+        path: PathBuf,
+        source: toml::de::Error,
+    },
+    Invalid {
+        path: PathBuf,
+        message: String,
+    },
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io { path, source } => write!(f, "failed to read {}: {source}", path.display()),
+            Self::Parse { path, source } => {
+                write!(f, "invalid config {}: {source}", path.display())
+            }
+            Self::Invalid { path, message } => {
+                write!(f, "invalid config {}: {message}", path.display())
+            }
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+impl Config {
+    /// `$XDG_CONFIG_HOME/bevy_wallpaper/config.toml`, falling back to `~/.config` when unset.
+    pub fn path() -> PathBuf {
+        let dir = env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+            .unwrap_or_default();
+
+        dir.join("bevy_wallpaper").join("config.toml")
+    }
+
+    /// loads the config from `Config::path`, or the defaults if that file doesn't exist.
+    pub fn load() -> Result<Self, ConfigError> {
+        Self::load_from(&Self::path())
+    }
+
+    /// loads the config from `path`, or the defaults if that file doesn't exist.
+    pub fn load_from(path: &Path) -> Result<Self, ConfigError> {
+        if path.exists() {
+            Self::from_file(path)
+        } else {
+            Ok(Self::default())
+        }
+    }
+
+    /// reads, parses and validates a config file.
+    pub fn from_file(path: &Path) -> Result<Self, ConfigError> {
+        let text = fs::read_to_string(path).map_err(|source| ConfigError::Io {
+            path: path.into(),
+            source,
+        })?;
+        let config: Self = toml::from_str(&text).map_err(|source| ConfigError::Parse {
+            path: path.into(),
+            source,
+        })?;
+
+        config.validate().map_err(|message| ConfigError::Invalid {
+            path: path.into(),
+            message,
+        })?;
+
+        Ok(config)
+    }
+
+    /// a warning for each deprecated key that's set, saying what replaces it.
+    pub fn deprecated(&self) -> Vec<String> {
+        let colors = self.colors.clone().unwrap_or_default();
+
+        [
+            ("colors.clear", colors.clear, "base"),
+            ("colors.wireframe", colors.wireframe, "highlight"),
+            ("sphere.color", self.sphere.color, "accent"),
+            (
+                "sphere.bounding_color",
+                self.sphere.bounding_color,
+                "overlay",
+            ),
+            (
+                "sphere.bounding_wireframe",
+                self.sphere.bounding_wireframe,
+                "mantle",
+            ),
+        ]
+        .into_iter()
+        .filter(|(_, color, _)| color.is_some())
+        .map(|(key, _, role)| format!("`{key}` is deprecated, set the palette's `{role}` instead"))
+        .collect()
+    }
+
+    /// the outputs to draw on. configured `outputs` come first, then `window`, then the
+    /// `detected` monitors, and if none of those are there a single default window. only the
+    /// one named by `output` is kept, and it's an error if it isn't there.
+    pub fn outputs(&self, detected: Option<&[OutputConfig]>) -> Result<Vec<OutputConfig>, String> {
+        let mut outputs = self.all_outputs(detected);
+
+        if let Some(name) = &self.output {
+            if !outputs.iter().any(|output| output.name == *name) {
+                let known = outputs
+                    .iter()
+                    .map(|output| output.name.as_str())
+                    .collect::<Vec<_>>();
+
+                return Err(format!(
+                    "no output named `{name}`, known: {}",
+                    known.join(", ")
+                ));
+            }
+
+            outputs.retain(|output| output.name == *name);
+        }
+
+        Ok(outputs)
+    }
+
+    fn all_outputs(&self, detected: Option<&[OutputConfig]>) -> Vec<OutputConfig> {
+        if !self.outputs.is_empty() {
+            return self.outputs.clone();
+        }
+
+        if let (None, Some(detected)) = (&self.window, detected.filter(|d| !d.is_empty())) {
+            return detected.to_vec();
+        }
+
+        let WindowConfig {
+            width,
+            height,
+            x,
+            y,
+        } = self.window.clone().unwrap_or_default();
+
+        vec![OutputConfig {
+            name: "default".into(),
+            width,
+            height,
+            x,
+            y,
+            mode: None,
+            yaw: 0.0,
+            scale: None,
+            physical: None,
+        }]
+    }
+
+    /// catches values that parse fine but can't be used.
+    fn validate(&self) -> Result<(), String> {
+        let mut sizes = Vec::new();
+
+        if let Some(WindowConfig { width, height, .. }) = self.window {
+            sizes.push(("window.width".to_string(), width));
+            sizes.push(("window.height".to_string(), height));
+        }
+
+        for output in &self.outputs {
+            if self
+                .outputs
+                .iter()
+                .filter(|o| o.name == output.name)
+                .count()
+                > 1
+            {
+                return Err(format!("more than one output is named `{}`", output.name));
+            }
+
+            sizes.push((format!("outputs.{}.width", output.name), output.width));
+            sizes.push((format!("outputs.{}.height", output.name), output.height));
+
+            if let Some(scale) = output.scale.filter(|s| !s.is_finite() || *s <= 0.0) {
+                return Err(format!(
+                    "outputs.{}.scale must be above 0, got {scale}",
+                    output.name
+                ));
+            }
+        }
+
+        if self.span {
+            if self.outputs.is_empty() {
+                return Err("span needs the outputs configured".into());
+            }
+
+            for output in &self.outputs {
+                let Some(physical) = output.physical else {
+                    return Err(format!(
+                        "outputs.{}.physical is needed for span",
+                        output.name
+                    ));
+                };
+
+                sizes.push((
+                    format!("outputs.{}.physical.width", output.name),
+                    physical.width,
+                ));
+                sizes.push((
+                    format!("outputs.{}.physical.height", output.name),
+                    physical.height,
+                ));
+            }
+        }
+
+        for (name, value) in sizes {
+            if !value.is_finite() || value < 1.0 {
+                return Err(format!("{name} must be at least 1, got {value}"));
+            }
+        }
+
+        if self.palette != PYWAL && Palette::named(self, &self.palette).is_none() {
+            let known = BUILT_IN
+                .into_iter()
+                .chain([PYWAL])
+                .chain(self.palettes.keys().map(String::as_str))
+                .collect::<Vec<_>>();
+
+            return Err(format!(
+                "unknown palette `{}`, expected one of {}",
+                self.palette,
+                known.join(", ")
+            ));
+        }
+
+        if let Some(fps) = self.fps.filter(|fps| !fps.is_finite() || *fps <= 0.0) {
+            return Err(format!("fps must be greater than 0, got {fps}"));
+        }
+
+        let reduced_fps = self.power.reduced_fps;
+
+        if !reduced_fps.is_finite() || reduced_fps <= 0.0 {
+            return Err(format!(
+                "power.reduced_fps must be greater than 0, got {reduced_fps}"
+            ));
+        }
+
+        let low_power_fps = self.space_stuff.low_power_fps;
+
+        if !low_power_fps.is_finite() || low_power_fps <= 0.0 {
+            return Err(format!(
+                "space_stuff.low_power_fps must be greater than 0, got {low_power_fps}"
+            ));
+        }
+
+        for (name, value) in [
+            ("time.max_delta", self.time.max_delta),
+            ("time.resume_gap", self.time.resume_gap),
+            ("sphere.period", self.sphere.period),
+            ("sphere.update_rate", self.sphere.update_rate),
+        ] {
+            if !value.is_finite() || value <= 0.0 {
+                return Err(format!("{name} must be greater than 0, got {value}"));
+            }
+        }
+
+        self.sphere.noise.validate()?;
+
+        let quality = &self.quality;
+
+        if quality.subdivisions > 79 {
+            return Err(format!(
+                "quality.subdivisions must be at most 79, got {}",
+                quality.subdivisions
+            ));
+        }
+
+        if quality.shadow_map_size == 0 {
+            return Err("quality.shadow_map_size must be greater than 0".into());
+        }
+
+        if ![1, 2, 4, 8].contains(&quality.msaa) {
+            return Err(format!(
+                "quality.msaa must be 1, 2, 4 or 8, got {}",
+                quality.msaa
+            ));
+        }
+
+        let spawn_interval = self.space_stuff.spawn_interval;
+
+        if !spawn_interval.is_finite() || spawn_interval <= 0.0 {
+            return Err(format!(
+                "space_stuff.spawn_interval must be greater than 0, got {spawn_interval}"
+            ));
+        }
+
+        for (name, value) in [
+            ("space_stuff.despawn_near", self.space_stuff.despawn_near),
+            ("space_stuff.despawn_fade", self.space_stuff.despawn_fade),
+        ] {
+            if !value.is_finite() || value < 0.0 {
+                return Err(format!("{name} must be 0 or greater, got {value}"));
+            }
+        }
+
+        if self.space_stuff.burst_size == 0 {
+            return Err("space_stuff.burst_size must be at least 1".into());
+        }
+
+        for (name, kind) in &self.space_stuff.kinds {
+            if let Some(weight) = kind.weight.filter(|w| !w.is_finite() || *w < 0.0) {
+                return Err(format!(
+                    "space_stuff.kinds.{name}.weight must be 0 or greater, got {weight}"
+                ));
+            }
+
+            if kind.one_in == Some(0) {
+                return Err(format!(
+                    "space_stuff.kinds.{name}.one_in must be at least 1"
+                ));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// settings from the command line or the environment, they win over the config file every time
+/// it's loaded.
+#[derive(Resource, Clone, Debug, Default, PartialEq)]
+pub struct Overrides {
+    pub mode: Option<Mode>,
+    pub palette: Option<String>,
+    pub output: Option<String>,
+    pub fps: Option<f32>,
+    pub seed: Option<u64>,
+    pub quality: Option<QualityPreset>,
+}
+
+impl Overrides {
+    /// `config` with the overrides applied, validated again since they may not fit it.
+    pub fn apply(&self, mut config: Config) -> Result<Config, String> {
+        if let Some(mode) = self.mode {
+            config.mode = mode;
+        }
+
+        if let Some(palette) = &self.palette {
+            config.palette = palette.clone();
+        }
+
+        if let Some(output) = &self.output {
+            config.output = Some(output.clone());
+        }
+
+        config.fps = self.fps.or(config.fps);
+        config.seed = self.seed.or(config.seed);
+        config.quality.preset = self.quality.unwrap_or(config.quality.preset);
+        config.validate()?;
+
+        Ok(config)
+    }
+}
+
+/// watches the config file and applies changes to the running app.
+pub struct ConfigPlugin {
+    /// the file to watch. default: `Config::path()`
+    pub path: PathBuf,
+    /// applied over the file after every reload.
+    pub overrides: Overrides,
+}
+
+impl Default for ConfigPlugin {
+    fn default() -> Self {
+        Self {
+            path: Config::path(),
+            overrides: Overrides::default(),
+        }
+    }
+}
+
+impl Plugin for ConfigPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Config>()
+            .insert_resource(self.overrides.clone())
+            .insert_resource(ConfigWatcher(FileWatcher::new(self.path.clone())))
+            .add_systems(
+                Update,
+                (
+                    reload_config,
+                    warn_deprecated.run_if(resource_changed::<Config>),
+                )
+                    .chain(),
+            );
+    }
+}
+
+/// polls a file's modification time, about once a second.
+pub(crate) struct FileWatcher {
+    pub(crate) path: PathBuf,
+    modified: Option<SystemTime>,
+    timer: Timer,
+}
+
+impl FileWatcher {
+    pub(crate) fn new(path: PathBuf) -> Self {
+        Self {
+            modified: modified(&path),
+            path,
+            timer: Timer::from_seconds(1.0, TimerMode::Repeating),
+        }
+    }
+
+    /// returns true when the file exists and was modified since the last poll.
+    pub(crate) fn poll(&mut self, delta: Duration) -> bool {
+        if !self.timer.tick(delta).just_finished() {
+            return false;
+        }
+
+        let modified = modified(&self.path);
+
+        if modified.is_none() || modified == self.modified {
+            return false;
+        }
+
+        self.modified = modified;
+
+        true
+    }
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|meta| meta.modified()).ok()
+}
+
+#[derive(Resource, Deref, DerefMut)]
+struct ConfigWatcher(FileWatcher);
+
+fn reload_config(
+    mut watcher: ResMut<ConfigWatcher>,
+    overrides: Res<Overrides>,
+    time: Res<Time<Real>>,
+    mut config: ResMut<Config>,
+    mut next_mode: ResMut<NextState<Mode>>,
+) {
+    if !watcher.poll(time.delta()) {
+        return;
+    }
+
+    let new_config = Config::from_file(&watcher.path).and_then(|new_config| {
+        overrides
+            .apply(new_config)
+            .map_err(|message| ConfigError::Invalid {
+                path: watcher.path.clone(),
+                message,
+            })
+    });
+    let new_config = match new_config {
+        Ok(new_config) => new_config,
+        Err(e) => {
+            error!("{e}, keeping the last good config");
+            return;
+        }
+    };
+
+    info!("reloaded {}", watcher.path.display());
+
+    // only switch modes when the file asks for a different one, so a mode picked with bg-cli
+    // survives unrelated edits.
+    if new_config.mode != config.mode {
+        next_mode.set(new_config.mode);
+    }
+
+    config.set_if_neq(new_config);
+}
+
+fn warn_deprecated(config: Res<Config>) {
+    for warning in config.deprecated() {
+        warn!("{warning}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    /// writes `text` to a config file in a fresh directory and loads it.
+    fn load(text: &str) -> Result<Config, String> {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("config.toml");
+        fs::write(&path, text).unwrap();
+
+        Config::from_file(&path).map_err(|e| match e {
+            ConfigError::Parse { source, .. } => source.message().to_string(),
+            ConfigError::Invalid { message, .. } => message,
+            ConfigError::Io { source, .. } => source.to_string(),
+        })
+    }
+
+    #[test]
+    fn a_valid_file_loads() {
+        let config = load(
+            r##"
+            mode = "space-stuff"
+            palette = "latte"
+            fps = 30
+
+            [quality]
+            preset = "custom"
+            subdivisions = 79
+            msaa = 8
+
+            [sphere.noise]
+            kind = "worley"
+            octaves = 6
+
+            [[outputs]]
+            name = "DP-1"
+            width = 1920
+            height = 1080
+            x = 0
+            y = 0
+            "##,
+        )
+        .unwrap();
+
+        assert_eq!(config.mode, Mode::SpaceStuff);
+        assert_eq!(config.palette, "latte");
+        assert_eq!(config.fps, Some(30.0));
+        assert_eq!(config.quality.subdivisions, 79);
+        assert_eq!(config.sphere.noise.kind, NoiseKind::Worley);
+        assert_eq!(config.sphere.noise.fractal, Fractal::None);
+        assert_eq!(config.outputs[0].name, "DP-1");
+        assert_eq!(config.power, PowerConfig::default());
+        assert_eq!(load("").unwrap(), Config::default());
+    }
+
+    #[test]
+    fn unknown_keys_are_rejected() {
+        let e = load("[sphere]\nperiod = 30\nperoid = 30").unwrap_err();
+
+        assert!(e.contains("unknown field `peroid`"), "{e}");
+    }
+
+    #[test]
+    fn values_out_of_range_are_rejected() {
+        for (text, message) in [
+            (
+                "quality.msaa = 3",
+                "quality.msaa must be 1, 2, 4 or 8, got 3",
+            ),
+            (
+                "quality.subdivisions = 80",
+                "quality.subdivisions must be at most 79, got 80",
+            ),
+            (
+                "space_stuff.low_power_fps = 0",
+                "space_stuff.low_power_fps must be greater than 0, got 0",
+            ),
+            (
+                "sphere.noise.octaves = 33",
+                "sphere.noise.octaves must be from 1 to 32, got 33",
+            ),
+            (
+                "space_stuff.kinds.asteroid.one_in = 0",
+                "space_stuff.kinds.asteroid.one_in must be at least 1",
+            ),
+            ("span = true", "span needs the outputs configured"),
+            (
+                r#"palette = "mochaa""#,
+                "unknown palette `mochaa`, expected one of latte, frappe, macchiato, mocha, pywal",
+            ),
+        ] {
+            assert_eq!(load(text).unwrap_err(), message);
+        }
+    }
+
+    #[test]
+    fn only_the_named_output_is_drawn_on() {
+        let mut config = load(
+            r#"
+            [[outputs]]
+            name = "DP-1"
+            width = 1920
+            height = 1080
+            x = 0
+            y = 0
+
+            [[outputs]]
+            name = "HDMI-1"
+            width = 1920
+            height = 1080
+            x = 1920
+            y = 0
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.outputs(None).unwrap().len(), 2);
+
+        config.output = Some("HDMI-1".into());
+        let outputs = config.outputs(None).unwrap();
+
+        assert_eq!(outputs.len(), 1);
+        assert_eq!(outputs[0].name, "HDMI-1");
+
+        config.output = Some("HDMI-2".into());
+
+        assert_eq!(
+            config.outputs(None).unwrap_err(),
+            "no output named `HDMI-2`, known: DP-1, HDMI-1"
+        );
+    }
+
+    #[test]
+    fn deprecated_colors_set_palette_roles() {
+        let config = load(
+            r##"
+            colors.clear = "#000000"
+            colors.wireframe = "#ffffff"
+            sphere.color = "#ff0000"
+            sphere.bounding_color = "#00ff00"
+            sphere.bounding_wireframe = "#0000ff"
+            "##,
+        )
+        .unwrap();
+        let palette = Palette::resolve(&config, "latte").unwrap();
+        let hex = |color: HexColor| color.to_hex();
+
+        assert_eq!(hex(palette.base), "#000000");
+        assert_eq!(hex(palette.highlight), "#FFFFFF");
+        assert_eq!(hex(palette.accent), "#FF0000");
+        assert_eq!(hex(palette.overlay), "#00FF00");
+        assert_eq!(hex(palette.mantle), "#0000FF");
+        assert_eq!(palette.surface, Palette::latte().surface);
+        assert_eq!(config.deprecated().len(), 5);
+        assert_eq!(
+            config.deprecated()[0],
+            "`colors.clear` is deprecated, set the palette's `base` instead"
+        );
+    }
+
+    #[test]
+    fn overrides_win_over_the_file_and_are_validated() {
+        let config = load("mode = \"sphere\"\nfps = 60\n[quality]\npreset = \"high\"").unwrap();
+        let overrides = Overrides {
+            mode: Some(Mode::SpaceStuff),
+            fps: Some(24.0),
+            quality: Some(QualityPreset::Low),
+            ..default()
+        };
+        let config = overrides.apply(config).unwrap();
+
+        assert_eq!(config.mode, Mode::SpaceStuff);
+        assert_eq!(config.fps, Some(24.0));
+        assert_eq!(config.quality.preset, QualityPreset::Low);
+
+        let bad = Overrides {
+            fps: Some(0.0),
+            ..default()
+        };
+
+        assert_eq!(
+            bad.apply(config).unwrap_err(),
+            "fps must be greater than 0, got 0"
+        );
+    }
+}
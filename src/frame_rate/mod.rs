@@ -0,0 +1,148 @@
+use crate::{
+    config::Config,
+    outputs::{ActiveModes, ModeViews},
+};
+use bevy::{
+    prelude::*,
+    winit::{self, UpdateMode, WakeUp},
+};
+use bevy_linux_wallpaper as wallpaper;
+use std::{sync::Arc, time::Duration};
+
+/// how long to wait between frames while paused. requests from bg-cli wake the loop up sooner.
+const PAUSED_FRAME_TIME: Duration = Duration::from_secs(1);
+
+/// wakes the event loop up from another thread, so something that happened there gets handled
+/// without waiting for the next frame.
+#[derive(Resource, Clone, Default)]
+pub struct EventLoopWaker(Option<Arc<dyn Fn() + Send + Sync>>);
+
+impl EventLoopWaker {
+    pub fn wake(&self) {
+        if let Some(wake) = &self.0 {
+            wake();
+        }
+    }
+}
+
+/// a further limit on the frame rate, from the power policies.
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq)]
+pub struct FpsCap(pub Option<f32>);
+
+/// the time between frames being aimed for, `None` to draw as often as vsync allows.
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq)]
+pub struct TargetFrameTime(pub Option<Duration>);
+
+/// draws frames only as often as needed: no more than `Config::fps` or the `FpsCap`, in low power
+/// mode only as fast as the modes on screen need to look smooth, and hardly at all while paused.
+pub struct FrameRatePlugin;
+
+impl Plugin for FrameRatePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Config>()
+            .init_resource::<ModeViews>()
+            .init_state::<ActiveModes>()
+            .init_resource::<FpsCap>()
+            .init_resource::<TargetFrameTime>()
+            .add_systems(
+                Update,
+                (
+                    update_target_frame_time,
+                    apply_target_frame_time.run_if(resource_changed::<TargetFrameTime>),
+                )
+                    .chain(),
+            );
+    }
+
+    /// the event loop proxy only exists once the wallpaper or winit plugin is built.
+    fn finish(&self, app: &mut App) {
+        let world = app.world();
+        let waker = if let Some(proxy) =
+            world.get_resource::<wallpaper::EventLoopProxyWrapper<WakeUp>>()
+        {
+            let proxy = (**proxy).clone();
+            EventLoopWaker(Some(Arc::new(move || {
+                let _ = proxy.send_event(WakeUp);
+            })))
+        } else if let Some(proxy) = world.get_resource::<winit::EventLoopProxyWrapper<WakeUp>>() {
+            let proxy = (**proxy).clone();
+            EventLoopWaker(Some(Arc::new(move || {
+                let _ = proxy.send_event(WakeUp);
+            })))
+        } else {
+            EventLoopWaker::default()
+        };
+
+        app.insert_resource(waker);
+    }
+}
+
+/// the frame rate to aim for, `None` for no limit.
+pub fn target_fps(
+    config: &Config,
+    cap: FpsCap,
+    needed: impl IntoIterator<Item = f32>,
+) -> Option<f32> {
+    let needed = needed
+        .into_iter()
+        .reduce(f32::max)
+        .filter(|_| config.low_power);
+
+    [config.fps, cap.0, needed]
+        .into_iter()
+        .flatten()
+        .reduce(f32::min)
+}
+
+fn update_target_frame_time(
+    config: Res<Config>,
+    views: Res<ModeViews>,
+    active: Res<State<ActiveModes>>,
+    cap: Res<FpsCap>,
+    time: Res<Time<Virtual>>,
+    mut target: ResMut<TargetFrameTime>,
+) {
+    let frame_time = if time.is_paused() {
+        Some(PAUSED_FRAME_TIME)
+    } else {
+        let needed = active
+            .get()
+            .0
+            .iter()
+            .filter_map(|mode| views.get(mode))
+            .map(|view| view.fps);
+
+        target_fps(&config, *cap, needed).map(|fps| Duration::from_secs_f32(1.0 / fps))
+    };
+
+    target.set_if_neq(TargetFrameTime(frame_time));
+}
+
+/// switches the event loop between drawing continuously and waiting between frames. both the
+/// wallpaper's and winit's settings are set, whichever one is driving the app reads its own.
+fn apply_target_frame_time(mut cmds: Commands, target: Res<TargetFrameTime>) {
+    match target.0 {
+        Some(frame_time) => info!("drawing at most {:.1} fps", 1.0 / frame_time.as_secs_f32()),
+        None => info!("drawing as often as vsync allows"),
+    }
+
+    let mode = match target.0 {
+        Some(frame_time) => wallpaper::UpdateMode::reactive_low_power(frame_time),
+        None => wallpaper::UpdateMode::Continuous,
+    };
+
+    cmds.insert_resource(wallpaper::WinitSettings {
+        focused_mode: mode,
+        unfocused_mode: mode,
+    });
+
+    let mode = match target.0 {
+        Some(frame_time) => UpdateMode::reactive_low_power(frame_time),
+        None => UpdateMode::Continuous,
+    };
+
+    cmds.insert_resource(winit::WinitSettings {
+        focused_mode: mode,
+        unfocused_mode: mode,
+    });
+}
@@ -0,0 +1,401 @@
+use crate::{
+    Mode,
+    config::{Config, NoiseChange, QualityPreset, SphereNoise},
+    frame_rate::EventLoopWaker,
+    outputs::Output,
+    palette::{Palette, PaletteName},
+    quality::SelectedQuality,
+    space_stuff::space_objects::SpaceThing,
+    sphere::{LoopPhase, SelectedNoise, SphereShown},
+};
+use bevy::{
+    diagnostic::{DiagnosticsStore, FrameTimeDiagnosticsPlugin},
+    ecs::system::SystemParam,
+    prelude::*,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    env,
+    fs::{self, DirBuilder},
+    io::{self, BufRead, BufReader, Write},
+    os::unix::{
+        fs::{DirBuilderExt, MetadataExt},
+        net::{UnixListener, UnixStream},
+    },
+    path::{Path, PathBuf},
+    sync::{
+        Mutex,
+        mpsc::{self, Receiver, Sender},
+    },
+    thread,
+    time::Duration,
+};
+
+/// name of the socket file inside `$XDG_RUNTIME_DIR`.
+pub const SOCKET_NAME: &str = "bevy_wallpaper.sock";
+/// how long either side waits for the other before giving up.
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+/// a command sent from bg-cli to bg-serv.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Request {
+    /// switch to a different wallpaper mode.
+    Mode(Mode),
+    /// freeze virtual time, stopping all animation.
+    Pause,
+    /// unfreeze virtual time.
+    Resume,
+    /// report the live renderer state.
+    Status,
+    /// switch to a named palette.
+    Palette(String),
+    /// switch to a quality preset.
+    Quality(QualityPreset),
+    /// change the noise the sphere undulates with.
+    Noise(NoiseChange),
+}
+
+/// bg-serv's answer to a `Request`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Response {
+    Ok,
+    Status(Status),
+    Error(String),
+}
+
+/// a snapshot of the running renderer, answered to `Request::Status`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Status {
+    pub mode: Mode,
+    pub paused: bool,
+    /// name of the palette in use.
+    pub palette: String,
+    pub quality: QualityPreset,
+    /// the noise the sphere undulates with.
+    pub noise: SphereNoise,
+    /// one window per output.
+    pub windows: Vec<WindowStatus>,
+    /// smoothed frame time in milliseconds.
+    pub frame_time_ms: Option<f64>,
+    /// smoothed frames per second.
+    pub fps: Option<f64>,
+    /// number of live `SpaceThing`s, keyed by kind.
+    pub space_things: BTreeMap<String, usize>,
+    /// how far through its loop the sphere is, from 0 up to 1. only present while an output
+    /// shows the sphere.
+    pub loop_phase: Option<f32>,
+    /// seconds since bg-serv started, including time spent paused.
+    pub uptime_secs: f64,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct WindowStatus {
+    /// the output this window draws.
+    pub output: String,
+    /// the mode shown in this window.
+    pub mode: Mode,
+    /// physical size in pixels.
+    pub resolution: [u32; 2],
+    /// top left corner, if the window was placed at an explicit position.
+    pub position: Option<[i32; 2]>,
+}
+
+/// a request waiting to be handled inside the app, along with where to send the answer.
+struct Message {
+    request: Request,
+    reply: Sender<Response>,
+}
+
+/// receiving end of the channel fed by the socket listener thread.
+#[derive(Resource)]
+struct Inbox {
+    messages: Mutex<Receiver<Message>>,
+    /// the socket file, removed on exit.
+    path: PathBuf,
+}
+
+/// listens on a per-user unix socket and applies bg-cli requests to the running app.
+#[derive(Default)]
+pub struct IpcPlugin;
+
+impl Plugin for IpcPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<FrameTimeDiagnosticsPlugin>() {
+            app.add_plugins(FrameTimeDiagnosticsPlugin);
+        }
+
+        app.add_systems(Startup, start_listener)
+            .add_systems(Update, handle_requests.run_if(resource_exists::<Inbox>))
+            .add_systems(
+                Last,
+                remove_socket.run_if(resource_exists::<Inbox>.and(on_event::<AppExit>)),
+            );
+    }
+}
+
+/// returns the path of the control socket, `$XDG_RUNTIME_DIR/bevy_wallpaper.sock`. without
+/// `XDG_RUNTIME_DIR` it goes in `/tmp/bevy_wallpaper-$UID/`, so other users can't reach it.
+pub fn socket_path() -> io::Result<PathBuf> {
+    let dir = match env::var_os("XDG_RUNTIME_DIR") {
+        Some(dir) => PathBuf::from(dir),
+        None => env::temp_dir().join(format!("bevy_wallpaper-{}", uid()?)),
+    };
+
+    Ok(dir.join(SOCKET_NAME))
+}
+
+/// the user running this process, who owns its `/proc/self`.
+fn uid() -> io::Result<u32> {
+    Ok(fs::metadata("/proc/self")?.uid())
+}
+
+/// creates `dir` for this user alone if it's missing, and refuses it if anyone else could get
+/// into it.
+fn make_private(dir: &Path) -> io::Result<()> {
+    match DirBuilder::new().mode(0o700).create(dir) {
+        Err(e) if e.kind() != io::ErrorKind::AlreadyExists => return Err(e),
+        _ => {}
+    }
+
+    let meta = fs::symlink_metadata(dir)?;
+
+    if !meta.is_dir() || meta.uid() != uid()? || meta.mode() & 0o077 != 0 {
+        return Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            format!(
+                "{} must be a directory only this user can access",
+                dir.display()
+            ),
+        ));
+    }
+
+    Ok(())
+}
+
+/// sends one request to bg-serv and waits for its response.
+pub fn send(request: &Request) -> io::Result<Response> {
+    let mut stream = UnixStream::connect(socket_path()?)?;
+    stream.set_read_timeout(Some(TIMEOUT))?;
+
+    let mut line = serde_json::to_string(request)?;
+    line.push('\n');
+    stream.write_all(line.as_bytes())?;
+
+    let mut reply = String::new();
+    BufReader::new(stream).read_line(&mut reply)?;
+
+    Ok(serde_json::from_str(&reply)?)
+}
+
+fn bind(path: &Path) -> io::Result<UnixListener> {
+    if let Some(dir) = path.parent() {
+        make_private(dir)?;
+    }
+
+    if path.exists() {
+        if UnixStream::connect(path).is_ok() {
+            return Err(io::Error::new(
+                io::ErrorKind::AddrInUse,
+                format!("another bg-serv is already listening on {}", path.display()),
+            ));
+        }
+
+        // left behind by a bg-serv that didn't shut down cleanly.
+        fs::remove_file(path)?;
+    }
+
+    UnixListener::bind(path)
+}
+
+fn start_listener(mut cmds: Commands, waker: Option<Res<EventLoopWaker>>) {
+    let waker = waker.map(|waker| waker.clone()).unwrap_or_default();
+    let (listener, path) = match socket_path().and_then(|path| Ok((bind(&path)?, path))) {
+        Ok(opened) => opened,
+        Err(e) => {
+            error!("failed to open control socket: {e}");
+            return;
+        }
+    };
+    let (tx, rx) = mpsc::channel();
+
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            match stream {
+                Ok(stream) => {
+                    if let Err(e) = serve_client(stream, &tx, &waker) {
+                        warn!("control client error: {e}");
+                    }
+                }
+                Err(e) => warn!("failed to accept control client: {e}"),
+            }
+        }
+    });
+
+    info!("listening for commands on {}", path.display());
+    cmds.insert_resource(Inbox {
+        messages: Mutex::new(rx),
+        path,
+    });
+}
+
+/// takes the socket file away with bg-serv, so bg-cli doesn't try to talk to nothing.
+fn remove_socket(inbox: Res<Inbox>) {
+    if let Err(e) = fs::remove_file(&inbox.path) {
+        warn!("failed to remove {}: {e}", inbox.path.display());
+    }
+}
+
+fn serve_client(
+    stream: UnixStream,
+    inbox: &Sender<Message>,
+    waker: &EventLoopWaker,
+) -> io::Result<()> {
+    stream.set_read_timeout(Some(TIMEOUT))?;
+
+    let mut line = String::new();
+    BufReader::new(&stream).read_line(&mut line)?;
+
+    let response = match serde_json::from_str(&line) {
+        Ok(request) => {
+            let (reply, answer) = mpsc::channel();
+            _ = inbox.send(Message { request, reply });
+            // the loop may be waiting a while for its next frame, e.g. while paused.
+            waker.wake();
+
+            answer
+                .recv_timeout(TIMEOUT)
+                .unwrap_or_else(|_| Response::Error("bg-serv did not answer in time".into()))
+        }
+        Err(e) => Response::Error(format!("malformed request: {e}")),
+    };
+
+    let mut reply = serde_json::to_string(&response)?;
+    reply.push('\n');
+    (&stream).write_all(reply.as_bytes())
+}
+
+/// everything `Request::Status` reads from the world.
+#[derive(SystemParam)]
+struct StatusQuery<'w, 's> {
+    mode: Res<'w, State<Mode>>,
+    palette: Res<'w, PaletteName>,
+    quality: Res<'w, SelectedQuality>,
+    noise: Res<'w, SelectedNoise>,
+    windows: Query<'w, 's, (&'static Window, &'static Output)>,
+    diagnostics: Res<'w, DiagnosticsStore>,
+    space_things: Query<'w, 's, &'static SpaceThing>,
+    loop_phase: Option<Res<'w, LoopPhase>>,
+    sphere_shown: Option<Res<'w, State<SphereShown>>>,
+    real_time: Res<'w, Time<Real>>,
+}
+
+impl StatusQuery<'_, '_> {
+    fn status(&self, paused: bool) -> Status {
+        let smoothed = |path| {
+            self.diagnostics
+                .get(path)
+                .and_then(|diagnostic| diagnostic.smoothed())
+        };
+        let mode = *self.mode.get();
+        let windows = self
+            .windows
+            .iter()
+            .map(|(window, output)| WindowStatus {
+                output: output.name.clone(),
+                mode: output.mode.unwrap_or(mode),
+                resolution: [window.physical_width(), window.physical_height()],
+                position: match window.position {
+                    WindowPosition::At(at) => Some(at.into()),
+                    _ => None,
+                },
+            })
+            .collect();
+        let mut space_things = BTreeMap::new();
+
+        for space_thing in self.space_things.iter() {
+            *space_things.entry(space_thing.kind().into()).or_default() += 1;
+        }
+
+        Status {
+            mode,
+            paused,
+            palette: self.palette.0.clone(),
+            quality: **self.quality,
+            noise: self.noise.0.clone(),
+            windows,
+            frame_time_ms: smoothed(&FrameTimeDiagnosticsPlugin::FRAME_TIME),
+            fps: smoothed(&FrameTimeDiagnosticsPlugin::FPS),
+            space_things,
+            loop_phase: self
+                .loop_phase
+                .as_ref()
+                .filter(|_| self.sphere_shown.is_some())
+                .map(|phase| ***phase),
+            uptime_secs: self.real_time.elapsed_secs_f64(),
+        }
+    }
+}
+
+/// the choices bg-cli can make over the config's.
+#[derive(SystemParam)]
+struct Selections<'w> {
+    palette: ResMut<'w, PaletteName>,
+    quality: ResMut<'w, SelectedQuality>,
+    noise: ResMut<'w, SelectedNoise>,
+}
+
+fn handle_requests(
+    inbox: Res<Inbox>,
+    mut next_mode: ResMut<NextState<Mode>>,
+    mut time: ResMut<Time<Virtual>>,
+    status: StatusQuery,
+    config: Res<Config>,
+    mut selections: Selections,
+) {
+    let Ok(inbox) = inbox.messages.lock() else {
+        return;
+    };
+
+    for Message { request, reply } in inbox.try_iter() {
+        debug!("handling request {request:?}");
+
+        let response = match request {
+            Request::Mode(mode) => {
+                next_mode.set(mode);
+                Response::Ok
+            }
+            Request::Pause => {
+                time.pause();
+                Response::Ok
+            }
+            Request::Resume => {
+                time.unpause();
+                Response::Ok
+            }
+            Request::Status => Response::Status(status.status(time.is_paused())),
+            Request::Palette(name) => match Palette::resolve(&config, &name) {
+                Ok(_) => {
+                    selections.palette.set_if_neq(PaletteName(name));
+                    Response::Ok
+                }
+                Err(e) => Response::Error(e),
+            },
+            Request::Quality(preset) => {
+                selections.quality.set_if_neq(SelectedQuality(preset));
+                Response::Ok
+            }
+            Request::Noise(change) => match change.apply(&selections.noise) {
+                Ok(changed) => {
+                    selections.noise.set_if_neq(SelectedNoise(changed));
+                    Response::Ok
+                }
+                Err(e) => Response::Error(e),
+            },
+        };
+
+        _ = reply.send(response);
+    }
+}
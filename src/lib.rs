@@ -1,9 +1,22 @@
 use bevy::{
     asset::RenderAssetUsages,
+    log::Level,
     prelude::*,
     render::render_resource::{Extent3d, TextureDimension, TextureFormat},
 };
+use clap::ValueEnum;
+use rand::{SeedableRng, rngs::StdRng};
+use serde::{Deserialize, Serialize};
+use std::fmt;
 
+pub mod clock;
+pub mod config;
+pub mod frame_rate;
+pub mod ipc;
+pub mod outputs;
+pub mod palette;
+pub mod power;
+pub mod quality;
 // pub mod space_objects;
 pub mod space_stuff;
 pub mod sphere;
@@ -12,16 +25,88 @@ pub mod sphere;
 #[derive(Component)]
 pub struct Shape;
 
-#[derive(Debug, States, Default, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(
+    Debug,
+    States,
+    Default,
+    Clone,
+    Copy,
+    Hash,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Serialize,
+    Deserialize,
+    ValueEnum,
+)]
+#[serde(rename_all = "kebab-case")]
 pub enum Mode {
     #[default]
     SpaceStuff,
     Sphere,
 }
 
+impl fmt::Display for Mode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.to_possible_value() {
+            Some(value) => f.write_str(value.get_name()),
+            None => write!(f, "{self:?}"),
+        }
+    }
+}
+
 #[derive(Component)]
 pub struct DebugTexture(pub Handle<StandardMaterial>);
 
+/// where everything random comes from, seeded from `Config::seed` so a run can be repeated.
+#[derive(Resource, Deref, DerefMut)]
+pub struct WallpaperRng(pub StdRng);
+
+impl WallpaperRng {
+    /// seeded with `seed`, or by the OS without one.
+    pub fn new(seed: Option<u64>) -> Self {
+        Self(match seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_os_rng(),
+        })
+    }
+}
+
+impl Default for WallpaperRng {
+    fn default() -> Self {
+        Self::new(None)
+    }
+}
+
+/// messages from before the app, and with it logging, was set up. `log_early` logs them once it
+/// is, at the level picked for the run.
+#[derive(Resource, Clone, Debug, Default)]
+pub struct EarlyLog(pub Vec<(Level, String)>);
+
+impl EarlyLog {
+    pub fn info(&mut self, message: impl Into<String>) {
+        self.0.push((Level::INFO, message.into()));
+    }
+
+    pub fn warn(&mut self, message: impl Into<String>) {
+        self.0.push((Level::WARN, message.into()));
+    }
+}
+
+/// logs and forgets everything in `EarlyLog`.
+pub fn log_early(mut early: ResMut<EarlyLog>) {
+    for (level, message) in early.0.drain(..) {
+        match level {
+            Level::ERROR => error!("{message}"),
+            Level::WARN => warn!("{message}"),
+            Level::INFO => info!("{message}"),
+            Level::DEBUG => debug!("{message}"),
+            Level::TRACE => trace!("{message}"),
+        }
+    }
+}
+
 /// Creates a colorful test pattern
 pub fn uv_debug_texture() -> Image {
     const TEXTURE_SIZE: usize = 8;
@@ -0,0 +1,583 @@
+use crate::{
+    EarlyLog, Mode,
+    config::{Config, OutputConfig},
+    frame_rate::EventLoopWaker,
+};
+use bevy::{
+    prelude::*,
+    render::{
+        camera::{RenderTarget, SubCameraView},
+        view::{RenderLayers, VisibilitySystems},
+    },
+    utils::HashMap,
+    window::{
+        PresentMode, PrimaryWindow, WindowLevel, WindowMode, WindowRef, WindowResized,
+        WindowResolution,
+    },
+};
+use std::{
+    collections::BTreeSet,
+    sync::{Mutex, mpsc::Receiver},
+};
+
+pub mod randr;
+
+/// a monitor the wallpaper is drawn on, attached to that monitor's window.
+#[derive(Component, Clone, Debug, PartialEq)]
+pub struct Output {
+    pub name: String,
+    /// the mode shown on this output, `None` follows the global `Mode`.
+    pub mode: Option<Mode>,
+    /// degrees to turn this output's camera around the scene, for a different view of it.
+    pub yaw: f32,
+    /// the screen's visible area on the wall in millimeters, y going down.
+    pub physical: Option<Rect>,
+}
+
+impl From<&OutputConfig> for Output {
+    fn from(output: &OutputConfig) -> Self {
+        Self {
+            name: output.name.clone(),
+            mode: output.mode,
+            yaw: output.yaw,
+            physical: output.physical.map(|physical| {
+                Rect::new(
+                    physical.x,
+                    physical.y,
+                    physical.x + physical.width,
+                    physical.y + physical.height,
+                )
+            }),
+        }
+    }
+}
+
+/// the camera drawing an output, points at the output's window entity.
+#[derive(Component, Clone, Copy, Debug)]
+pub struct OutputCamera {
+    pub window: Entity,
+    /// the mode this camera is currently set up for.
+    pub mode: Mode,
+    pub yaw: f32,
+    /// the output's part of the scene when it spans several outputs.
+    pub sub_view: Option<SubCameraView>,
+}
+
+/// the set of modes shown on at least one output. each mode plugin derives its own state from
+/// this to know when to build and tear down its scene.
+#[derive(States, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct ActiveModes(pub BTreeSet<Mode>);
+
+/// marks an entity as part of a mode's scene, so it's only drawn by that mode's cameras and gets
+/// despawned with the rest of the scene.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ModeEntity(pub Mode);
+
+/// how a mode wants its scene filmed, registered by each mode plugin.
+#[derive(Clone, Debug)]
+pub struct ModeView {
+    pub transform: Transform,
+    pub projection: PerspectiveProjection,
+    /// the lowest frame rate the mode's animation still looks smooth at, used in low power mode.
+    pub fps: f32,
+}
+
+impl ModeView {
+    /// the frame rate that keeps something moving across the view at `speed` units per second,
+    /// `distance` in front of the camera, within `SMOOTH_STEP` of the view's height a frame.
+    pub fn smooth_fps(&self, speed: f32, distance: f32) -> f32 {
+        let view_height = 2.0 * distance * (self.projection.fov / 2.0).tan();
+
+        speed / (view_height * SMOOTH_STEP)
+    }
+}
+
+/// how far something can jump from one frame to the next and still look like it's moving
+/// smoothly, as a share of the view's height. about 5 pixels on a 1080p screen.
+const SMOOTH_STEP: f32 = 1.0 / 200.0;
+
+#[derive(Resource, Default, Deref, DerefMut)]
+pub struct ModeViews(HashMap<Mode, ModeView>);
+
+/// the monitors found through RandR, `None` if they couldn't be queried.
+#[derive(Resource, Clone, Debug, Default, PartialEq)]
+pub struct Monitors(pub Option<Vec<OutputConfig>>);
+
+impl Monitors {
+    /// queries RandR, noting what it found in `log`, or why it couldn't.
+    pub fn detect(log: &mut EarlyLog) -> Self {
+        match randr::detect() {
+            Ok(monitors) => {
+                for monitor in &monitors {
+                    log.info(format!(
+                        "found monitor {} {}x{} at ({}, {})",
+                        monitor.name, monitor.width, monitor.height, monitor.x, monitor.y
+                    ));
+                }
+
+                Self(Some(monitors))
+            }
+            Err(e) => {
+                log.warn(format!("failed to detect monitors: {e}"));
+                Self(None)
+            }
+        }
+    }
+}
+
+/// new monitor layouts from the RandR watcher thread.
+#[derive(Resource)]
+struct MonitorChanges(Mutex<Receiver<Vec<OutputConfig>>>);
+
+/// the render layer each mode's scene is drawn on.
+pub fn render_layers(mode: Mode) -> RenderLayers {
+    match mode {
+        Mode::SpaceStuff => RenderLayers::layer(1),
+        Mode::Sphere => RenderLayers::layer(2),
+    }
+}
+
+/// components every entity in `mode`'s scene should carry.
+pub fn in_mode(mode: Mode) -> (ModeEntity, RenderLayers) {
+    (ModeEntity(mode), render_layers(mode))
+}
+
+/// the wallpaper window for one output.
+pub fn output_window(output: &OutputConfig) -> Window {
+    Window {
+        present_mode: PresentMode::AutoVsync,
+        name: Some("wallpaper".into()),
+        title: format!("wallpaper ({})", output.name),
+        window_level: WindowLevel::AlwaysOnBottom,
+        mode: WindowMode::Windowed,
+        skip_taskbar: false,
+        titlebar_shown: false,
+        resolution: resolution(output),
+        position: WindowPosition::At((output.x, output.y).into()),
+        fullsize_content_view: true,
+        ..Default::default()
+    }
+}
+
+/// `output`'s size, which RandR and the config both give in physical pixels, at its scale.
+fn resolution(output: &OutputConfig) -> WindowResolution {
+    let mut resolution = WindowResolution::default();
+    resolution.set_scale_factor_override(output.scale);
+    resolution.set_physical_resolution(output.width as u32, output.height as u32);
+
+    resolution
+}
+
+/// gives every output its own window and camera, and works out which modes need to run.
+pub struct OutputsPlugin {
+    /// create a window per output in the config, or per monitor, and follow monitors being
+    /// plugged in and out. when off, only the primary window is used and it shows the global
+    /// `Mode`.
+    pub from_config: bool,
+}
+
+impl Default for OutputsPlugin {
+    fn default() -> Self {
+        Self { from_config: true }
+    }
+}
+
+impl Plugin for OutputsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Config>()
+            .init_resource::<Monitors>()
+            .init_resource::<ModeViews>()
+            .init_state::<ActiveModes>()
+            .add_systems(
+                Update,
+                (update_active_modes, sync_cameras)
+                    .chain()
+                    .after(adopt_primary_window),
+            )
+            .add_systems(
+                PostUpdate,
+                propagate_mode_entities.before(VisibilitySystems::CheckVisibility),
+            );
+
+        if self.from_config {
+            app.add_systems(Startup, watch_monitors).add_systems(
+                Update,
+                (
+                    update_monitors.run_if(resource_exists::<MonitorChanges>),
+                    sync_outputs
+                        .run_if(resource_changed::<Config>.or(resource_changed::<Monitors>)),
+                    keep_output_geometry,
+                )
+                    .chain()
+                    .before(adopt_primary_window),
+            );
+        }
+
+        app.add_systems(Update, adopt_primary_window);
+    }
+}
+
+fn watch_monitors(mut cmds: Commands, waker: Option<Res<EventLoopWaker>>) {
+    match randr::watch(waker.map(|waker| waker.clone()).unwrap_or_default()) {
+        Ok(changes) => cmds.insert_resource(MonitorChanges(Mutex::new(changes))),
+        Err(e) => warn!("not watching for monitor changes: {e}"),
+    }
+}
+
+fn update_monitors(changes: Res<MonitorChanges>, mut monitors: ResMut<Monitors>) {
+    let Ok(changes) = changes.0.lock() else {
+        return;
+    };
+
+    if let Some(latest) = changes.try_iter().last() {
+        info!("monitor layout changed, {} connected", latest.len());
+        monitors.set_if_neq(Monitors(Some(latest)));
+    }
+}
+
+/// puts output windows back when something else resizes or moves them, e.g. the X server
+/// shuffling windows around after a monitor is unplugged.
+fn keep_output_geometry(
+    config: Res<Config>,
+    monitors: Res<Monitors>,
+    mut resized: EventReader<WindowResized>,
+    mut moved: EventReader<WindowMoved>,
+    mut windows: Query<(&mut Window, &Output)>,
+) {
+    let changed = resized
+        .read()
+        .map(|e| e.window)
+        .chain(moved.read().map(|e| e.window))
+        .collect::<Vec<_>>();
+
+    if changed.is_empty() {
+        return;
+    }
+
+    let Ok(wanted) = config.outputs(monitors.0.as_deref()) else {
+        return;
+    };
+
+    for entity in changed {
+        let Ok((mut window, output)) = windows.get_mut(entity) else {
+            continue;
+        };
+        let Some(geometry) = wanted.iter().find(|o| o.name == output.name) else {
+            continue;
+        };
+
+        if place_window(&mut window, geometry) {
+            debug!("put output {} back in place", output.name);
+        }
+    }
+}
+
+/// whether `window` already has `output`'s size, position and scale.
+fn is_placed(window: &Window, output: &OutputConfig) -> bool {
+    let wanted = resolution(output);
+
+    window.resolution.scale_factor_override() == wanted.scale_factor_override()
+        && window.physical_width() == wanted.physical_width()
+        && window.physical_height() == wanted.physical_height()
+        && window.position == WindowPosition::At(IVec2::new(output.x, output.y))
+}
+
+/// resizes and moves `window` onto `output`, returns whether anything changed.
+fn place_window(window: &mut Window, output: &OutputConfig) -> bool {
+    if is_placed(window, output) {
+        return false;
+    }
+
+    let wanted = resolution(output);
+    window
+        .resolution
+        .set_scale_factor_override(wanted.scale_factor_override());
+    window
+        .resolution
+        .set_physical_resolution(wanted.physical_width(), wanted.physical_height());
+    window.position = WindowPosition::At(IVec2::new(output.x, output.y));
+
+    true
+}
+
+/// spawns and despawns output windows to match the config and the connected monitors, and
+/// rebuilds any whose size, position or scale changed. the primary window always belongs to the
+/// first output.
+fn sync_outputs(
+    mut cmds: Commands,
+    config: Res<Config>,
+    monitors: Res<Monitors>,
+    primary: Option<Single<Entity, With<PrimaryWindow>>>,
+    mut windows: Query<(Entity, &mut Window, Option<&mut Output>)>,
+) {
+    let wanted = match config.outputs(monitors.0.as_deref()) {
+        Ok(wanted) => wanted,
+        Err(e) => {
+            warn!("{e}, keeping the current windows");
+            return;
+        }
+    };
+    let primary = primary.map(|primary| *primary);
+    let mut by_name = HashMap::new();
+
+    for (entity, _, output) in &windows {
+        if let Some(output) = output {
+            by_name.insert(output.name.clone(), entity);
+        }
+    }
+
+    let mut kept = Vec::with_capacity(wanted.len());
+    let mut rebuilt = Vec::new();
+
+    for (i, output) in wanted.iter().enumerate() {
+        let existing = if i == 0 {
+            primary
+        } else {
+            by_name
+                .get(&output.name)
+                .copied()
+                .filter(|e| Some(*e) != primary)
+        };
+
+        let Some(entity) = existing else {
+            info!("adding output {}", output.name);
+            kept.push(
+                cmds.spawn((output_window(output), Output::from(output)))
+                    .id(),
+            );
+            continue;
+        };
+
+        let Ok((_, window, current)) = windows.get_mut(entity) else {
+            continue;
+        };
+
+        if !is_placed(&window, output) {
+            // respawned rather than resized and moved, the wallpaper backend only sets a window
+            // up as the desktop background when it's created. its camera follows it.
+            info!("rebuilding output {}", output.name);
+            cmds.entity(entity).despawn_recursive();
+            rebuilt.push(entity);
+
+            let mut window = cmds.spawn((output_window(output), Output::from(output)));
+
+            if Some(entity) == primary {
+                window.insert(PrimaryWindow);
+            }
+
+            kept.push(window.id());
+            continue;
+        }
+
+        kept.push(entity);
+
+        match current {
+            Some(mut current) => {
+                current.set_if_neq(Output::from(output));
+            }
+            None => {
+                cmds.entity(entity).insert(Output::from(output));
+            }
+        }
+    }
+
+    for (entity, _, output) in &windows {
+        if let Some(output) =
+            output.filter(|_| !kept.contains(&entity) && !rebuilt.contains(&entity))
+        {
+            info!("removing output {}", output.name);
+            cmds.entity(entity).despawn_recursive();
+        }
+    }
+}
+
+/// makes sure the primary window counts as an output even when outputs don't come from the
+/// config, e.g. in the windowed preview.
+fn adopt_primary_window(
+    mut cmds: Commands,
+    primary: Query<Entity, (With<PrimaryWindow>, Without<Output>)>,
+) {
+    for primary in &primary {
+        cmds.entity(primary).insert(Output {
+            name: "primary".into(),
+            mode: None,
+            yaw: 0.0,
+            physical: None,
+        });
+    }
+}
+
+fn update_active_modes(
+    mode: Res<State<Mode>>,
+    outputs: Query<&Output>,
+    active: Res<State<ActiveModes>>,
+    mut next_active: ResMut<NextState<ActiveModes>>,
+) {
+    let wanted = ActiveModes(
+        outputs
+            .iter()
+            .map(|output| output.mode.unwrap_or(*mode.get()))
+            .collect(),
+    );
+
+    if *active.get() != wanted {
+        next_active.set(wanted);
+    }
+}
+
+/// sub views are measured in tenths of a millimeter.
+const SUB_VIEW_UNITS_PER_MM: f32 = 10.0;
+
+/// the part of one scene spanning every output in `span` that `physical` shows. the scene is
+/// filmed as if all the screens were one, so the bezels hide a bit of it like a window frame
+/// would, and things moving across them line up.
+pub fn span_sub_view(physical: Rect, span: &[Rect]) -> SubCameraView {
+    let full = span
+        .iter()
+        .fold(physical, |full, screen| full.union(*screen));
+    let to_units = |mm: Vec2| (mm * SUB_VIEW_UNITS_PER_MM).round();
+
+    SubCameraView {
+        full_size: to_units(full.size()).as_uvec2().max(UVec2::ONE),
+        offset: to_units(physical.min - full.min),
+        size: to_units(physical.size()).as_uvec2().max(UVec2::ONE),
+    }
+}
+
+/// keeps one camera per output, set up the way the output's mode wants its scene filmed.
+fn sync_cameras(
+    mut cmds: Commands,
+    config: Res<Config>,
+    mode: Res<State<Mode>>,
+    views: Res<ModeViews>,
+    outputs: Query<(Entity, &Output)>,
+    cameras: Query<(Entity, &OutputCamera)>,
+) {
+    let mode_of = |output: &Output| output.mode.unwrap_or(*mode.get());
+    let sub_view = |output: &Output| {
+        let physical = output.physical.filter(|_| config.span)?;
+        let span = outputs
+            .iter()
+            .filter(|(_, other)| mode_of(other) == mode_of(output))
+            .filter_map(|(_, other)| other.physical)
+            .collect::<Vec<_>>();
+
+        Some(span_sub_view(physical, &span))
+    };
+    let mut filmed = Vec::new();
+
+    for (entity, camera) in &cameras {
+        let Ok((_, output)) = outputs.get(camera.window) else {
+            cmds.entity(entity).despawn_recursive();
+            continue;
+        };
+
+        if camera.mode == mode_of(output)
+            && camera.yaw == output.yaw
+            && camera.sub_view == sub_view(output)
+        {
+            filmed.push(camera.window);
+            continue;
+        }
+
+        // respawned rather than patched, so nothing from the last mode's setup lingers.
+        cmds.entity(entity).despawn_recursive();
+    }
+
+    for (window, output) in &outputs {
+        if filmed.contains(&window) {
+            continue;
+        }
+
+        let mode = mode_of(output);
+        let Some(view) = views.get(&mode) else {
+            continue;
+        };
+        let turn = Transform::from_rotation(Quat::from_rotation_y(output.yaw.to_radians()));
+        let sub_view = sub_view(output);
+
+        cmds.spawn((
+            Camera3d::default(),
+            Camera {
+                target: RenderTarget::Window(WindowRef::Entity(window)),
+                sub_camera_view: sub_view,
+                ..default()
+            },
+            turn * view.transform,
+            Projection::Perspective(view.projection.clone()),
+            render_layers(mode),
+            OutputCamera {
+                window,
+                mode,
+                yaw: output.yaw,
+                sub_view,
+            },
+        ));
+    }
+}
+
+/// meshes loaded from scenes (e.g. gltf models) don't inherit their root's render layers, this
+/// copies a scene root's `ModeEntity` and layers down to them.
+fn propagate_mode_entities(
+    mut cmds: Commands,
+    added: Query<Entity, (Added<Mesh3d>, Without<ModeEntity>)>,
+    parents: Query<&Parent>,
+    mode_entities: Query<&ModeEntity>,
+) {
+    for entity in &added {
+        let root_mode = parents
+            .iter_ancestors(entity)
+            .find_map(|ancestor| mode_entities.get(ancestor).ok());
+
+        if let Some(ModeEntity(mode)) = root_mode {
+            cmds.entity(entity).insert(in_mode(*mode));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn output(scale: Option<f32>) -> OutputConfig {
+        OutputConfig {
+            name: "DP-1".into(),
+            width: 3840.0,
+            height: 2160.0,
+            x: 1920,
+            y: 0,
+            mode: None,
+            yaw: 0.0,
+            scale,
+            physical: None,
+        }
+    }
+
+    #[test]
+    fn windows_are_sized_in_physical_pixels() {
+        for scale in [None, Some(1.0), Some(2.0)] {
+            let window = output_window(&output(scale));
+
+            assert_eq!(
+                (window.physical_width(), window.physical_height()),
+                (3840, 2160)
+            );
+            assert_eq!(window.resolution.scale_factor_override(), scale);
+            assert!(is_placed(&window, &output(scale)));
+        }
+
+        assert_eq!(output_window(&output(Some(2.0))).width(), 1920.0);
+    }
+
+    #[test]
+    fn placing_a_window_moves_and_rescales_it() {
+        let mut window = output_window(&output(None));
+        let mut moved = output(Some(1.5));
+        moved.x = 0;
+
+        assert!(place_window(&mut window, &moved));
+        assert!(is_placed(&window, &moved));
+        assert_eq!(window.physical_width(), 3840);
+        assert!(!place_window(&mut window, &moved));
+    }
+}
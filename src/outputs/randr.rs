@@ -0,0 +1,238 @@
+use crate::{config::OutputConfig, frame_rate::EventLoopWaker};
+use bevy::log::warn;
+use std::{
+    sync::mpsc::{self, Receiver},
+    thread,
+};
+use x11rb::{
+    connection::Connection,
+    errors::ReplyError,
+    protocol::{
+        Event,
+        randr::{self, ConnectionExt as _, GetCrtcInfoReply, GetOutputInfoReply, NotifyMask},
+        xproto::Window,
+    },
+    rust_connection::RustConnection,
+};
+
+/// the DPI a scale factor of 1 stands for.
+const BASE_DPI: f32 = 96.0;
+
+/// the connected monitors and where they sit on the X screen, the primary monitor first.
+pub fn detect() -> Result<Vec<OutputConfig>, String> {
+    let (conn, screen) = x11rb::connect(None).map_err(|e| e.to_string())?;
+    let root = conn.setup().roots[screen].root;
+
+    query(&conn, root).map_err(|e| e.to_string())
+}
+
+/// starts a thread that sends the new monitor layout every time a monitor is plugged in,
+/// unplugged, moved or rotated, waking the event loop up to handle it.
+pub fn watch(waker: EventLoopWaker) -> Result<Receiver<Vec<OutputConfig>>, String> {
+    let (conn, screen) = x11rb::connect(None).map_err(|e| e.to_string())?;
+    let root = conn.setup().roots[screen].root;
+    let mask = NotifyMask::SCREEN_CHANGE | NotifyMask::OUTPUT_CHANGE | NotifyMask::CRTC_CHANGE;
+
+    conn.randr_select_input(root, mask)
+        .map_err(|e| e.to_string())?
+        .check()
+        .map_err(|e| e.to_string())?;
+
+    let (send, recv) = mpsc::channel();
+
+    thread::spawn(move || {
+        loop {
+            let event = match conn.wait_for_event() {
+                Ok(event) => event,
+                Err(e) => {
+                    warn!("stopped watching for monitor changes: {e}");
+                    return;
+                }
+            };
+
+            if !is_layout_change(&event) {
+                continue;
+            }
+
+            // one change usually comes as a burst of events, only query once for all of them.
+            while let Ok(Some(_)) = conn.poll_for_event() {}
+
+            match query(&conn, root) {
+                Ok(monitors) => {
+                    if send.send(monitors).is_err() {
+                        return;
+                    }
+
+                    waker.wake();
+                }
+                Err(e) => warn!("failed to query monitors: {e}"),
+            }
+        }
+    });
+
+    Ok(recv)
+}
+
+fn is_layout_change(event: &Event) -> bool {
+    matches!(
+        event,
+        Event::RandrScreenChangeNotify(_) | Event::RandrNotify(_)
+    )
+}
+
+fn query(conn: &RustConnection, root: Window) -> Result<Vec<OutputConfig>, ReplyError> {
+    let resources = conn.randr_get_screen_resources_current(root)?.reply()?;
+    let primary = conn.randr_get_output_primary(root)?.reply()?.output;
+    let mut monitors = Vec::new();
+
+    for output in resources.outputs {
+        let info = conn
+            .randr_get_output_info(output, resources.config_timestamp)?
+            .reply()?;
+
+        if !is_on(&info) {
+            continue;
+        }
+
+        let crtc = conn
+            .randr_get_crtc_info(info.crtc, resources.config_timestamp)?
+            .reply()?;
+
+        if let Some(monitor) = monitor(&info, &crtc) {
+            monitors.push((output == primary, monitor));
+        }
+    }
+
+    Ok(arrange(monitors))
+}
+
+/// whether an output is connected and switched on, so it has a crtc to ask about.
+fn is_on(info: &GetOutputInfoReply) -> bool {
+    info.connection == randr::Connection::CONNECTED && info.crtc != 0
+}
+
+/// the monitor an output and the crtc driving it show up as, `None` when it shows nothing.
+fn monitor(info: &GetOutputInfoReply, crtc: &GetCrtcInfoReply) -> Option<OutputConfig> {
+    if crtc.width == 0 || crtc.height == 0 {
+        return None;
+    }
+
+    Some(OutputConfig {
+        name: String::from_utf8_lossy(&info.name).into_owned(),
+        // the crtc's size is already turned for rotated monitors.
+        width: crtc.width.into(),
+        height: crtc.height.into(),
+        x: crtc.x.into(),
+        y: crtc.y.into(),
+        mode: None,
+        yaw: 0.0,
+        scale: Some(scale_factor(
+            [crtc.width.into(), crtc.height.into()],
+            [info.mm_width as f32, info.mm_height as f32],
+        )),
+        physical: None,
+    })
+}
+
+/// the primary monitor first, then the rest from left to right and top to bottom.
+fn arrange(mut monitors: Vec<(bool, OutputConfig)>) -> Vec<OutputConfig> {
+    monitors.sort_by_key(|(primary, monitor)| (!primary, monitor.x, monitor.y));
+
+    monitors.into_iter().map(|(_, monitor)| monitor).collect()
+}
+
+/// the scale factor for a monitor of `size` pixels and `mm` millimeters, from its DPI rounded to
+/// a quarter. monitors that don't report their size get 1.
+pub fn scale_factor(size: [f32; 2], mm: [f32; 2]) -> f32 {
+    let diagonal_mm = mm[0].hypot(mm[1]);
+
+    if diagonal_mm <= 0.0 {
+        return 1.0;
+    }
+
+    let dpi = size[0].hypot(size[1]) / (diagonal_mm / 25.4);
+
+    ((dpi / BASE_DPI * 4.0).round() / 4.0).max(1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn info(name: &str, mm: [u32; 2]) -> GetOutputInfoReply {
+        GetOutputInfoReply {
+            name: name.into(),
+            connection: randr::Connection::CONNECTED,
+            crtc: 1,
+            mm_width: mm[0],
+            mm_height: mm[1],
+            ..Default::default()
+        }
+    }
+
+    fn crtc(x: i16, y: i16, width: u16, height: u16) -> GetCrtcInfoReply {
+        GetCrtcInfoReply {
+            x,
+            y,
+            width,
+            height,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn monitor_takes_the_crtc_geometry() {
+        let monitor = monitor(&info("DP-1", [527, 296]), &crtc(1920, 0, 1920, 1080)).unwrap();
+
+        assert_eq!(monitor.name, "DP-1");
+        assert_eq!((monitor.width, monitor.height), (1920.0, 1080.0));
+        assert_eq!((monitor.x, monitor.y), (1920, 0));
+        assert_eq!(monitor.scale, Some(1.0));
+        assert_eq!(monitor.mode, None);
+        assert_eq!(monitor.physical, None);
+    }
+
+    #[test]
+    fn rotated_monitors_are_taller_than_wide() {
+        let monitor = monitor(&info("HDMI-1", [527, 296]), &crtc(0, 0, 1080, 1920)).unwrap();
+
+        assert_eq!((monitor.width, monitor.height), (1080.0, 1920.0));
+        assert_eq!(monitor.scale, Some(1.0));
+    }
+
+    #[test]
+    fn dense_monitors_are_scaled() {
+        // a 27 inch 4k monitor, about 163 dpi.
+        let monitor = monitor(&info("DP-2", [597, 336]), &crtc(0, 0, 3840, 2160)).unwrap();
+
+        assert_eq!(monitor.scale, Some(1.75));
+        // without a size there's no telling.
+        assert_eq!(scale_factor([3840.0, 2160.0], [0.0, 0.0]), 1.0);
+    }
+
+    #[test]
+    fn monitors_showing_nothing_are_left_out() {
+        let mut off = info("DP-3", [527, 296]);
+        off.crtc = 0;
+        let mut disconnected = info("DP-4", [527, 296]);
+        disconnected.connection = randr::Connection::DISCONNECTED;
+
+        assert!(is_on(&info("DP-1", [527, 296])));
+        assert!(!is_on(&off));
+        assert!(!is_on(&disconnected));
+        assert!(monitor(&info("DP-1", [527, 296]), &crtc(0, 0, 0, 0)).is_none());
+    }
+
+    #[test]
+    fn primary_comes_first_then_left_to_right() {
+        let at = |name: &str, x: i16| monitor(&info(name, [0, 0]), &crtc(x, 0, 1920, 1080));
+        let arranged = arrange(vec![
+            (false, at("right", 3840).unwrap()),
+            (false, at("left", 0).unwrap()),
+            (true, at("middle", 1920).unwrap()),
+        ]);
+        let names = arranged.iter().map(|m| m.name.as_str()).collect::<Vec<_>>();
+
+        assert_eq!(names, ["middle", "left", "right"]);
+    }
+}
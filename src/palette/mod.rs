@@ -0,0 +1,238 @@
+use crate::config::{Config, FileWatcher, HexColor};
+use bevy::{pbr::wireframe::WireframeConfig, prelude::*};
+use pywal::{PYWAL, WalColors};
+use serde::{Deserialize, Serialize};
+
+pub mod pywal;
+
+/// names of the built in palettes, the catppuccin flavors.
+pub const BUILT_IN: [&str; 4] = ["latte", "frappe", "macchiato", "mocha"];
+
+/// the colors every mode draws with, by role.
+#[derive(Clone, Debug, PartialEq, Resource, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Palette {
+    /// the background.
+    pub base: HexColor,
+    /// slightly darker than base, used for the bounding sphere's wireframe.
+    pub mantle: HexColor,
+    /// the darkest background shade.
+    pub crust: HexColor,
+    /// the main subject, e.g. the undulating sphere.
+    pub accent: HexColor,
+    /// a secondary accent, used for wireframes.
+    pub highlight: HexColor,
+    /// raised surfaces, a little lighter than base.
+    pub surface: HexColor,
+    /// translucent overlays, e.g. the bounding sphere.
+    pub overlay: HexColor,
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Self::mocha()
+    }
+}
+
+fn rgb(r: u8, g: u8, b: u8) -> HexColor {
+    HexColor(Srgba::rgb_u8(r, g, b))
+}
+
+impl Palette {
+    /// catppuccin latte.
+    pub fn latte() -> Self {
+        Self {
+            base: rgb(239, 241, 245),
+            mantle: rgb(230, 233, 239),
+            crust: rgb(220, 224, 232),
+            accent: rgb(254, 100, 11),
+            highlight: rgb(64, 160, 43),
+            surface: rgb(204, 208, 218),
+            overlay: rgb(124, 127, 147),
+        }
+    }
+
+    /// catppuccin frappé.
+    pub fn frappe() -> Self {
+        Self {
+            base: rgb(48, 52, 70),
+            mantle: rgb(41, 44, 60),
+            crust: rgb(35, 38, 52),
+            accent: rgb(239, 159, 118),
+            highlight: rgb(166, 209, 137),
+            surface: rgb(65, 69, 89),
+            overlay: rgb(148, 156, 187),
+        }
+    }
+
+    /// catppuccin macchiato.
+    pub fn macchiato() -> Self {
+        Self {
+            base: rgb(36, 39, 58),
+            mantle: rgb(30, 32, 48),
+            crust: rgb(24, 25, 38),
+            accent: rgb(245, 169, 127),
+            highlight: rgb(166, 218, 149),
+            surface: rgb(54, 58, 79),
+            overlay: rgb(147, 154, 183),
+        }
+    }
+
+    /// catppuccin mocha.
+    pub fn mocha() -> Self {
+        Self {
+            base: rgb(30, 30, 46),
+            mantle: rgb(24, 24, 37),
+            crust: rgb(17, 17, 27),
+            accent: rgb(250, 179, 135),
+            highlight: rgb(166, 227, 161),
+            surface: rgb(49, 50, 68),
+            overlay: rgb(147, 153, 178),
+        }
+    }
+
+    /// looks a palette up by name, user defined palettes in `config` shadow the built in ones.
+    pub fn named(config: &Config, name: &str) -> Option<Self> {
+        if let Some(palette) = config.palettes.get(name) {
+            return Some(palette.clone());
+        }
+
+        match name {
+            "latte" => Some(Self::latte()),
+            "frappe" | "frappé" => Some(Self::frappe()),
+            "macchiato" => Some(Self::macchiato()),
+            "mocha" => Some(Self::mocha()),
+            _ => None,
+        }
+    }
+
+    /// like `named`, but also reads the colors pywal generated when `name` is `"pywal"`. colors
+    /// from the config's deprecated keys go on top.
+    pub fn resolve(config: &Config, name: &str) -> Result<Self, String> {
+        let palette = if name == PYWAL && !config.palettes.contains_key(PYWAL) {
+            WalColors::from_file(&config.pywal.path()).map(Self::from)?
+        } else {
+            Self::named(config, name).ok_or_else(|| format!("unknown palette `{name}`"))?
+        };
+
+        Ok(palette.with_deprecated(config))
+    }
+
+    /// this palette with the roles set through `[colors]` and `[sphere]` before there were
+    /// palettes.
+    fn with_deprecated(mut self, config: &Config) -> Self {
+        let colors = config.colors.clone().unwrap_or_default();
+
+        for (role, color) in [
+            (&mut self.base, colors.clear),
+            (&mut self.highlight, colors.wireframe),
+            (&mut self.accent, config.sphere.color),
+            (&mut self.overlay, config.sphere.bounding_color),
+            (&mut self.mantle, config.sphere.bounding_wireframe),
+        ] {
+            if let Some(color) = color {
+                *role = color;
+            }
+        }
+
+        self
+    }
+}
+
+/// the name of the palette currently in use.
+#[derive(Clone, Debug, PartialEq, Resource, Deref, DerefMut)]
+pub struct PaletteName(pub String);
+
+impl Default for PaletteName {
+    fn default() -> Self {
+        Self("mocha".into())
+    }
+}
+
+/// watches pywal's color file while the `"pywal"` palette is in use.
+#[derive(Resource, Default)]
+struct PywalWatcher(Option<FileWatcher>);
+
+/// keeps the `Palette` resource in sync with the config and recolors the scene when it changes.
+#[derive(Default)]
+pub struct PalettePlugin;
+
+impl Plugin for PalettePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Config>()
+            .init_resource::<Palette>()
+            .init_resource::<PaletteName>()
+            .init_resource::<PywalWatcher>()
+            .add_systems(
+                Update,
+                (
+                    select_palette.run_if(resource_changed::<Config>),
+                    load_palette,
+                    apply_palette.run_if(resource_changed::<Palette>),
+                )
+                    .chain(),
+            );
+    }
+}
+
+/// switches palettes when the config's choice of palette changes. a palette picked with bg-cli
+/// survives unrelated config edits.
+fn select_palette(
+    config: Res<Config>,
+    mut name: ResMut<PaletteName>,
+    mut selected: Local<Option<String>>,
+) {
+    if selected.as_ref() != Some(&config.palette) {
+        name.set_if_neq(PaletteName(config.palette.clone()));
+        *selected = Some(config.palette.clone());
+    }
+}
+
+/// (re)loads the palette's colors when a different one is picked, the config changes, or pywal
+/// writes new colors.
+fn load_palette(
+    config: Res<Config>,
+    name: Res<PaletteName>,
+    mut palette: ResMut<Palette>,
+    mut pywal: ResMut<PywalWatcher>,
+    time: Res<Time<Real>>,
+) {
+    let pywal_changed = if **name == PYWAL {
+        let path = config.pywal.path();
+        let watcher = pywal
+            .0
+            .get_or_insert_with(|| FileWatcher::new(path.clone()));
+
+        if watcher.path != path {
+            *watcher = FileWatcher::new(path);
+        }
+
+        watcher.poll(time.delta())
+    } else {
+        pywal.0 = None;
+        false
+    };
+
+    if !(name.is_changed() || config.is_changed() || pywal_changed) {
+        return;
+    }
+
+    match Palette::resolve(&config, &name) {
+        Ok(loaded) => {
+            palette.set_if_neq(loaded);
+        }
+        Err(e) => warn!("{e}, keeping the current colors"),
+    }
+}
+
+fn apply_palette(
+    palette: Res<Palette>,
+    mut clear_color: ResMut<ClearColor>,
+    wireframe: Option<ResMut<WireframeConfig>>,
+) {
+    clear_color.0 = palette.base.into();
+
+    if let Some(mut wireframe) = wireframe {
+        wireframe.default_color = palette.highlight.into();
+    }
+}
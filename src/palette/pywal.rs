@@ -0,0 +1,301 @@
+use super::Palette;
+use crate::config::HexColor;
+use bevy::prelude::*;
+use serde::Deserialize;
+use std::{collections::HashMap, fs, path::Path};
+
+/// the palette name that reads colors generated by pywal or wallust.
+pub const PYWAL: &str = "pywal";
+
+/// the colors pywal and friends generate from a wallpaper.
+#[derive(Clone, Debug, PartialEq)]
+pub struct WalColors {
+    pub background: Srgba,
+    pub foreground: Srgba,
+    /// the 16 terminal colors, `color0` through `color15`.
+    pub colors: [Srgba; 16],
+}
+
+/// layout of pywal's `colors.json`, which wallust can also emit.
+#[derive(Deserialize)]
+struct ColorsJson {
+    special: HashMap<String, String>,
+    colors: HashMap<String, String>,
+}
+
+impl WalColors {
+    /// reads a `colors.json` or an Xresources style file, whichever `path` holds.
+    pub fn from_file(path: &Path) -> Result<Self, String> {
+        let text = fs::read_to_string(path)
+            .map_err(|e| format!("failed to read {}: {e}", path.display()))?;
+
+        if text.trim_start().starts_with('{') {
+            Self::from_json(&text)
+        } else {
+            Self::from_xresources(&text)
+        }
+        .map_err(|e| format!("invalid color file {}: {e}", path.display()))
+    }
+
+    /// parses pywal's `colors.json`.
+    pub fn from_json(text: &str) -> Result<Self, String> {
+        let json: ColorsJson = serde_json::from_str(text).map_err(|e| e.to_string())?;
+
+        Self::from_lookup(|name| {
+            json.special
+                .get(name)
+                .or_else(|| json.colors.get(name))
+                .map(String::as_str)
+        })
+    }
+
+    /// parses lines like `*.color4: #81a2be` or `URxvt*background: [100]#1d1f21`.
+    pub fn from_xresources(text: &str) -> Result<Self, String> {
+        let mut resources = HashMap::new();
+
+        for line in text.lines().map(str::trim) {
+            if line.starts_with('!') || line.starts_with('#') {
+                continue;
+            }
+
+            let Some((key, value)) = line.split_once(':') else {
+                continue;
+            };
+            let (program, name) = key.rsplit_once(['*', '.']).unwrap_or(("", key));
+            let (name, generic) = (name.trim(), program.trim_matches(['*', '.']).is_empty());
+            // urxvt's transparency prefix, e.g. `[90]#1d1f21`.
+            let value = value.trim();
+            let value = value.rsplit(']').next().unwrap_or(value);
+
+            // generic `*name` and `*.name` resources win over program specific ones wherever they
+            // are, otherwise the first program's is kept.
+            match resources.get(name) {
+                Some((_, true)) => {}
+                Some((_, false)) if !generic => {}
+                _ => {
+                    resources.insert(name.to_string(), (value, generic));
+                }
+            }
+        }
+
+        Self::from_lookup(|name| resources.get(name).map(|(value, _)| *value))
+    }
+
+    fn from_lookup<'a>(lookup: impl Fn(&str) -> Option<&'a str>) -> Result<Self, String> {
+        let color = |name: &str| {
+            let hex = lookup(name).ok_or_else(|| format!("missing `{name}`"))?;
+
+            Srgba::hex(hex).map_err(|_| format!("`{name}` is not a hex color: `{hex}`"))
+        };
+        let mut colors = [Srgba::NONE; 16];
+
+        for (i, slot) in colors.iter_mut().enumerate() {
+            *slot = color(&format!("color{i}"))?;
+        }
+
+        Ok(Self {
+            background: color("background")?,
+            foreground: color("foreground")?,
+            colors,
+        })
+    }
+}
+
+impl From<WalColors> for Palette {
+    /// base is the background with mantle and crust as darker shades of it, surface sits a
+    /// step towards the foreground. the accents come from the wallpaper-derived colors 1 and 2,
+    /// and the overlay from the light grey color 7.
+    fn from(wal: WalColors) -> Self {
+        let background = wal.background;
+
+        Self {
+            base: HexColor(background),
+            mantle: HexColor(background.mix(&Srgba::BLACK, 0.15)),
+            crust: HexColor(background.mix(&Srgba::BLACK, 0.3)),
+            accent: HexColor(wal.colors[1]),
+            highlight: HexColor(wal.colors[2]),
+            surface: HexColor(background.mix(&wal.foreground, 0.15)),
+            overlay: HexColor(wal.colors[7]),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// pywal's `colors.json` for a sandy wallpaper.
+    const JSON: &str = r##"{
+    "wallpaper": "/home/me/Pictures/dunes.jpg",
+    "alpha": "100",
+
+    "special": {
+        "background": "#141416",
+        "foreground": "#c3c4c7",
+        "cursor": "#c3c4c7"
+    },
+    "colors": {
+        "color0": "#141416",
+        "color1": "#7A5C61",
+        "color2": "#8C6E6A",
+        "color3": "#A58874",
+        "color4": "#6F7E91",
+        "color5": "#8A8CA0",
+        "color6": "#A1A5B3",
+        "color7": "#c3c4c7",
+        "color8": "#5b5b63",
+        "color9": "#7A5C61",
+        "color10": "#8C6E6A",
+        "color11": "#A58874",
+        "color12": "#6F7E91",
+        "color13": "#8A8CA0",
+        "color14": "#A1A5B3",
+        "color15": "#c3c4c7"
+    }
+}"##;
+
+    /// pywal's `colors.Xresources` for the same wallpaper, where only the terminal colors are
+    /// generic and the rest are per program.
+    const XRESOURCES: &str = r##"! X colors.
+! Generated by 'wal'
+emacs*foreground:   #c3c4c7
+emacs*background:   #141416
+URxvt*foreground:   #c3c4c7
+XTerm*foreground:   #c3c4c7
+UXTerm*foreground:  #c3c4c7
+URxvt*background:   [100]#141416
+XTerm*background:   #141416
+UXTerm*background:  #141416
+URxvt*cursorColor:  #c3c4c7
+XTerm*cursorColor:  #c3c4c7
+UXTerm*cursorColor: #c3c4c7
+URxvt*borderColor:  [100]#141416
+
+! Colors 0-15.
+*.color0: #141416
+*color0:  #141416
+*.color1: #7A5C61
+*color1:  #7A5C61
+*.color2: #8C6E6A
+*color2:  #8C6E6A
+*.color3: #A58874
+*color3:  #A58874
+*.color4: #6F7E91
+*color4:  #6F7E91
+*.color5: #8A8CA0
+*color5:  #8A8CA0
+*.color6: #A1A5B3
+*color6:  #A1A5B3
+*.color7: #c3c4c7
+*color7:  #c3c4c7
+*.color8: #5b5b63
+*color8:  #5b5b63
+*.color9: #7A5C61
+*color9:  #7A5C61
+*.color10: #8C6E6A
+*color10:  #8C6E6A
+*.color11: #A58874
+*color11:  #A58874
+*.color12: #6F7E91
+*color12:  #6F7E91
+*.color13: #8A8CA0
+*color13:  #8A8CA0
+*.color14: #A1A5B3
+*color14:  #A1A5B3
+*.color15: #c3c4c7
+*color15:  #c3c4c7
+
+! Black color that will not be affected by bold highlighting.
+*.color66: #141416
+
+! Xclock colors.
+XClock*foreground: #c3c4c7
+XClock*background: #141416
+XClock*majorColor:  rgba:c3/c4/c7/ff
+XClock*minorColor:  rgba:c3/c4/c7/ff
+XClock*hourColor:   rgba:c3/c4/c7/ff
+XClock*minuteColor: rgba:c3/c4/c7/ff
+XClock*secondColor: rgba:c3/c4/c7/ff
+
+! Set depth to make transparency work.
+URxvt*depth: 32
+"##;
+
+    fn hex(color: Srgba) -> String {
+        color.to_hex()
+    }
+
+    #[test]
+    fn reads_colors_json() {
+        let wal = WalColors::from_json(JSON).unwrap();
+
+        assert_eq!(hex(wal.background), "#141416");
+        assert_eq!(hex(wal.foreground), "#C3C4C7");
+        assert_eq!(hex(wal.colors[1]), "#7A5C61");
+        assert_eq!(hex(wal.colors[15]), "#C3C4C7");
+    }
+
+    #[test]
+    fn reads_xresources_like_colors_json() {
+        assert_eq!(
+            WalColors::from_xresources(XRESOURCES).unwrap(),
+            WalColors::from_json(JSON).unwrap()
+        );
+    }
+
+    #[test]
+    fn generic_resources_win_over_program_specific_ones() {
+        let text = "URxvt*foreground: #ff0000\n*.foreground: #00ff00\n*foreground: #0000ff\n";
+        let text = XRESOURCES.replace("! X colors.", text);
+
+        assert_eq!(
+            hex(WalColors::from_xresources(&text).unwrap().foreground),
+            "#00FF00"
+        );
+    }
+
+    #[test]
+    fn urxvt_transparency_is_ignored() {
+        let text = XRESOURCES.replace(
+            "emacs*background:   #141416",
+            "URxvt*background: [90]#202024",
+        );
+
+        assert_eq!(
+            hex(WalColors::from_xresources(&text).unwrap().background),
+            "#202024"
+        );
+    }
+
+    #[test]
+    fn a_missing_color_is_an_error() {
+        let json = JSON.replace(r#""color15""#, r#""color16""#);
+        let xresources = XRESOURCES.replace("color15", "color16");
+
+        assert_eq!(
+            WalColors::from_json(&json).unwrap_err(),
+            "missing `color15`"
+        );
+        assert_eq!(
+            WalColors::from_xresources(&xresources).unwrap_err(),
+            "missing `color15`"
+        );
+        assert_eq!(
+            WalColors::from_xresources("*.color0: nope").unwrap_err(),
+            "`color0` is not a hex color: `nope`"
+        );
+    }
+
+    #[test]
+    fn palette_roles_come_from_the_wallpaper() {
+        let palette = Palette::from(WalColors::from_json(JSON).unwrap());
+
+        assert_eq!(hex(palette.base.0), "#141416");
+        assert_eq!(hex(palette.accent.0), "#7A5C61");
+        assert_eq!(hex(palette.highlight.0), "#8C6E6A");
+        assert_eq!(hex(palette.overlay.0), "#C3C4C7");
+        assert!(palette.crust.0.red < palette.mantle.0.red);
+        assert!(palette.mantle.0.red < palette.base.0.red);
+        assert!(palette.surface.0.red > palette.base.0.red);
+    }
+}
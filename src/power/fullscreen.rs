@@ -0,0 +1,102 @@
+use bevy::math::IRect;
+use x11rb::{
+    atom_manager,
+    connection::Connection,
+    errors::ReplyError,
+    protocol::xproto::{AtomEnum, ConnectionExt as _, MapState, Window},
+    rust_connection::RustConnection,
+};
+
+atom_manager! {
+    Atoms: AtomsCookie {
+        _NET_CLIENT_LIST,
+        _NET_WM_STATE,
+        _NET_WM_STATE_FULLSCREEN,
+    }
+}
+
+/// finds fullscreen windows through the window manager's EWMH hints.
+pub struct FullscreenWindows {
+    conn: RustConnection,
+    root: Window,
+    atoms: Atoms,
+}
+
+impl FullscreenWindows {
+    pub fn connect() -> Result<Self, String> {
+        let (conn, screen) = x11rb::connect(None).map_err(|e| e.to_string())?;
+        let root = conn.setup().roots[screen].root;
+        let atoms = Atoms::new(&conn)
+            .map_err(|e| e.to_string())?
+            .reply()
+            .map_err(|e| e.to_string())?;
+
+        Ok(Self { conn, root, atoms })
+    }
+
+    /// where the visible fullscreen windows are on the X screen.
+    pub fn find(&self) -> Result<Vec<IRect>, ReplyError> {
+        let clients = self
+            .conn
+            .get_property(
+                false,
+                self.root,
+                self.atoms._NET_CLIENT_LIST,
+                AtomEnum::WINDOW,
+                0,
+                u32::MAX,
+            )?
+            .reply()?;
+        let mut found = Vec::new();
+
+        for window in clients.value32().into_iter().flatten() {
+            // windows can close while they're being looked at, those are skipped.
+            if let Ok(Some(rect)) = self.fullscreen_rect(window) {
+                found.push(rect);
+            }
+        }
+
+        Ok(found)
+    }
+
+    fn fullscreen_rect(&self, window: Window) -> Result<Option<IRect>, ReplyError> {
+        let state = self
+            .conn
+            .get_property(
+                false,
+                window,
+                self.atoms._NET_WM_STATE,
+                AtomEnum::ATOM,
+                0,
+                u32::MAX,
+            )?
+            .reply()?;
+        let fullscreen = state
+            .value32()
+            .into_iter()
+            .flatten()
+            .any(|atom| atom == self.atoms._NET_WM_STATE_FULLSCREEN);
+
+        // minimized windows and ones on other workspaces aren't viewable.
+        if !fullscreen
+            || self.conn.get_window_attributes(window)?.reply()?.map_state != MapState::VIEWABLE
+        {
+            return Ok(None);
+        }
+
+        let geometry = self.conn.get_geometry(window)?.reply()?;
+        let at = self
+            .conn
+            .translate_coordinates(window, self.root, 0, 0)?
+            .reply()?;
+        let x = i32::from(at.dst_x);
+        let y = i32::from(at.dst_y);
+
+        Ok(Some(IRect::new(
+            x,
+            y,
+            x + i32::from(geometry.width),
+            y + i32::from(geometry.height),
+        )))
+    }
+}
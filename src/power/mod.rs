@@ -0,0 +1,232 @@
+use crate::{
+    Mode,
+    config::{Config, PowerPolicy},
+    frame_rate::{EventLoopWaker, FpsCap},
+    outputs::Output,
+};
+use bevy::prelude::*;
+use fullscreen::FullscreenWindows;
+use std::{
+    path::PathBuf,
+    sync::{
+        Mutex,
+        mpsc::{self, Receiver},
+    },
+    thread,
+    time::Duration,
+};
+
+pub mod fullscreen;
+pub mod supply;
+
+/// how often the power supply and fullscreen windows are checked.
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// the things power policies react to.
+#[derive(Resource, Clone, Debug, Default, PartialEq)]
+pub struct PowerState {
+    pub on_battery: bool,
+    /// the visible fullscreen windows.
+    pub fullscreen: Vec<IRect>,
+}
+
+/// what the power policies ask for at the moment.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+struct PowerSaving {
+    pause: bool,
+    fps: Option<f32>,
+    mode: Option<Mode>,
+}
+
+/// what the policies last did, so it can be undone.
+#[derive(Default)]
+struct Applied {
+    saving: PowerSaving,
+    /// whether time was paused by a policy rather than by bg-cli.
+    paused: bool,
+    /// the mode to go back to after `"switch-mode"`, if it's still showing the mode switched to.
+    resume_mode: Option<Mode>,
+}
+
+/// new power states from the watcher thread.
+#[derive(Resource)]
+struct PowerChanges(Mutex<Receiver<PowerState>>);
+
+/// pauses, slows down or switches modes on battery and while fullscreen windows cover the
+/// wallpaper, as set in `Config::power`.
+pub struct PowerPlugin;
+
+impl Plugin for PowerPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Config>()
+            .init_resource::<PowerState>()
+            .init_resource::<FpsCap>()
+            .add_systems(Startup, start_watching)
+            .add_systems(
+                Update,
+                (
+                    update_power_state.run_if(resource_exists::<PowerChanges>),
+                    apply_power_policies,
+                )
+                    .chain(),
+            );
+    }
+}
+
+/// whether every one of `outputs` sits inside one of the `fullscreen` windows.
+pub fn covered(fullscreen: &[IRect], outputs: &[IRect]) -> bool {
+    !outputs.is_empty()
+        && outputs.iter().all(|output| {
+            fullscreen
+                .iter()
+                .any(|window| window.intersect(*output) == *output)
+        })
+}
+
+fn start_watching(mut cmds: Commands, config: Res<Config>, waker: Option<Res<EventLoopWaker>>) {
+    let changes = watch(
+        config.power.supply_path.clone(),
+        waker.map(|waker| waker.clone()).unwrap_or_default(),
+    );
+
+    cmds.insert_resource(PowerChanges(Mutex::new(changes)));
+}
+
+/// starts a thread that sends the power state every time it changes.
+fn watch(supply_path: PathBuf, waker: EventLoopWaker) -> Receiver<PowerState> {
+    let (send, recv) = mpsc::channel();
+
+    thread::spawn(move || {
+        let mut fullscreen = FullscreenWindows::connect()
+            .inspect_err(|e| warn!("not looking for fullscreen windows: {e}"))
+            .ok();
+        let mut supply_failed = false;
+        let mut last = None;
+
+        loop {
+            let on_battery = supply::on_battery(&supply_path).unwrap_or_else(|e| {
+                if !supply_failed {
+                    warn!("failed to read {}: {e}", supply_path.display());
+                    supply_failed = true;
+                }
+
+                false
+            });
+            let found = match fullscreen.as_ref().map(FullscreenWindows::find) {
+                Some(Ok(found)) => found,
+                Some(Err(e)) => {
+                    warn!("stopped looking for fullscreen windows: {e}");
+                    fullscreen = None;
+                    Vec::new()
+                }
+                None => Vec::new(),
+            };
+            let state = PowerState {
+                on_battery,
+                fullscreen: found,
+            };
+
+            if last.as_ref() != Some(&state) {
+                if send.send(state.clone()).is_err() {
+                    return;
+                }
+
+                waker.wake();
+                last = Some(state);
+            }
+
+            thread::sleep(POLL_INTERVAL);
+        }
+    });
+
+    recv
+}
+
+fn update_power_state(changes: Res<PowerChanges>, mut state: ResMut<PowerState>) {
+    let Ok(changes) = changes.0.lock() else {
+        return;
+    };
+
+    if let Some(latest) = changes.try_iter().last() {
+        state.set_if_neq(latest);
+    }
+}
+
+fn apply_power_policies(
+    config: Res<Config>,
+    state: Res<PowerState>,
+    windows: Query<&Window, With<Output>>,
+    (mode, mut next_mode): (Res<State<Mode>>, ResMut<NextState<Mode>>),
+    mut time: ResMut<Time<Virtual>>,
+    mut fps_cap: ResMut<FpsCap>,
+    mut applied: Local<Applied>,
+) {
+    let outputs = windows
+        .iter()
+        .filter_map(|window| match window.position {
+            WindowPosition::At(at) => Some(IRect::from_corners(
+                at,
+                at + UVec2::new(window.physical_width(), window.physical_height()).as_ivec2(),
+            )),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    let conditions = [
+        ("on battery", state.on_battery, config.power.on_battery),
+        (
+            "covered",
+            covered(&state.fullscreen, &outputs),
+            config.power.covered,
+        ),
+    ];
+    let mut wanted = PowerSaving::default();
+
+    for (_, _, policy) in conditions.iter().filter(|(_, active, _)| *active) {
+        match policy {
+            PowerPolicy::Run => {}
+            PowerPolicy::Pause => wanted.pause = true,
+            PowerPolicy::ReduceFps => wanted.fps = Some(config.power.reduced_fps),
+            PowerPolicy::SwitchMode => wanted.mode = Some(config.power.cheap_mode),
+        }
+    }
+
+    if wanted == applied.saving {
+        return;
+    }
+
+    let reasons = conditions
+        .iter()
+        .filter(|(_, active, _)| *active)
+        .map(|(reason, _, _)| *reason)
+        .collect::<Vec<_>>();
+    info!("power saving {wanted:?}, {}", reasons.join(", "));
+
+    // a pause from bg-cli is left alone.
+    if wanted.pause && !applied.saving.pause && !time.is_paused() {
+        time.pause();
+        applied.paused = true;
+    } else if !wanted.pause && applied.paused {
+        time.unpause();
+        applied.paused = false;
+    }
+
+    if wanted.mode != applied.saving.mode {
+        match wanted.mode {
+            Some(cheap_mode) => {
+                applied.resume_mode.get_or_insert(*mode.get());
+                next_mode.set(cheap_mode);
+            }
+            None => {
+                // a mode picked with bg-cli since the switch is kept.
+                if let Some(resume_mode) = applied.resume_mode.take()
+                    && Some(*mode.get()) == applied.saving.mode
+                {
+                    next_mode.set(resume_mode);
+                }
+            }
+        }
+    }
+
+    fps_cap.set_if_neq(FpsCap(wanted.fps));
+    applied.saving = wanted;
+}
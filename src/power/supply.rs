@@ -0,0 +1,111 @@
+use std::{fs, io, path::Path};
+
+/// whether the machine is running on battery, going by the power supplies under `root`, normally
+/// `/sys/class/power_supply`. machines without a battery never are.
+pub fn on_battery(root: &Path) -> io::Result<bool> {
+    let mut chargers_online = Vec::new();
+    let mut has_battery = false;
+    let mut discharging = false;
+
+    for entry in fs::read_dir(root)? {
+        let dir = entry?.path();
+        let read = |name: &str| {
+            fs::read_to_string(dir.join(name))
+                .map(|value| value.trim().to_string())
+                .ok()
+        };
+
+        match read("type").as_deref() {
+            Some("Mains" | "USB") => chargers_online.push(read("online").as_deref() == Some("1")),
+            // mice and headsets report their batteries too, `scope` tells those apart.
+            Some("Battery") if read("scope").as_deref() != Some("Device") => {
+                has_battery = true;
+                discharging |= read("status").as_deref() == Some("Discharging");
+            }
+            _ => {}
+        }
+    }
+
+    // without a charger to ask, the battery's own status has to do.
+    let unplugged = if chargers_online.is_empty() {
+        discharging
+    } else {
+        !chargers_online.contains(&true)
+    };
+
+    Ok(has_battery && unplugged)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    /// a fake `/sys/class/power_supply` with a directory of `(file, contents)` per supply.
+    fn supplies(supplies: &[(&str, &[(&str, &str)])]) -> TempDir {
+        let root = tempfile::tempdir().unwrap();
+
+        for (name, files) in supplies {
+            let dir = root.path().join(name);
+            fs::create_dir(&dir).unwrap();
+
+            for (file, contents) in *files {
+                fs::write(dir.join(file), format!("{contents}\n")).unwrap();
+            }
+        }
+
+        root
+    }
+
+    const BATTERY: (&str, &[(&str, &str)]) = (
+        "BAT0",
+        &[
+            ("type", "Battery"),
+            ("scope", "System"),
+            ("status", "Discharging"),
+        ],
+    );
+
+    #[test]
+    fn charger_online() {
+        let root = supplies(&[("AC", &[("type", "Mains"), ("online", "1")]), BATTERY]);
+
+        assert!(!on_battery(root.path()).unwrap());
+    }
+
+    #[test]
+    fn charger_offline() {
+        let root = supplies(&[("AC", &[("type", "Mains"), ("online", "0")]), BATTERY]);
+
+        assert!(on_battery(root.path()).unwrap());
+    }
+
+    #[test]
+    fn discharging_battery_alone() {
+        let root = supplies(&[BATTERY]);
+
+        assert!(on_battery(root.path()).unwrap());
+    }
+
+    #[test]
+    fn device_batteries_dont_count() {
+        let root = supplies(&[(
+            "hidpp_battery_0",
+            &[
+                ("type", "Battery"),
+                ("scope", "Device"),
+                ("status", "Discharging"),
+            ],
+        )]);
+
+        assert!(!on_battery(root.path()).unwrap());
+    }
+
+    #[test]
+    fn no_supplies() {
+        let root = supplies(&[]);
+
+        assert!(!on_battery(root.path()).unwrap());
+        assert!(on_battery(&root.path().join("missing")).is_err());
+    }
+}
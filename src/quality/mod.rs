@@ -0,0 +1,241 @@
+use crate::config::{Config, QualityConfig, QualityPreset, Tonemap};
+use bevy::{core_pipeline::tonemapping::Tonemapping, pbr::PointLightShadowMap, prelude::*};
+
+/// the preset in use. follows the config, and can be switched with bg-cli.
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Deref, DerefMut)]
+pub struct SelectedQuality(pub QualityPreset);
+
+/// what the selected preset means for drawing.
+#[derive(Resource, Clone, Copy, Debug, PartialEq)]
+pub struct Quality {
+    /// how many times the sphere's icosahedron is subdivided.
+    pub subdivisions: u32,
+    pub shadows: bool,
+    pub shadow_map_size: usize,
+    pub msaa: Msaa,
+    pub tonemapping: Tonemapping,
+}
+
+impl Default for Quality {
+    fn default() -> Self {
+        Self::of(QualityPreset::Medium, &QualityConfig::default())
+    }
+}
+
+impl Quality {
+    /// the settings for `preset`, `custom` is only read for `QualityPreset::Custom`.
+    pub fn of(preset: QualityPreset, custom: &QualityConfig) -> Self {
+        match preset {
+            QualityPreset::Low => Self {
+                subdivisions: 5,
+                shadows: false,
+                shadow_map_size: 512,
+                msaa: Msaa::Off,
+                tonemapping: Tonemapping::None,
+            },
+            QualityPreset::Medium => Self {
+                subdivisions: 20,
+                shadows: true,
+                shadow_map_size: 1024,
+                msaa: Msaa::Sample4,
+                tonemapping: Tonemapping::TonyMcMapface,
+            },
+            QualityPreset::High => Self {
+                subdivisions: 60,
+                shadows: true,
+                shadow_map_size: 4096,
+                msaa: Msaa::Sample4,
+                tonemapping: Tonemapping::TonyMcMapface,
+            },
+            QualityPreset::Custom => Self {
+                subdivisions: custom.subdivisions,
+                shadows: custom.shadows,
+                shadow_map_size: custom.shadow_map_size,
+                msaa: match custom.msaa {
+                    1 => Msaa::Off,
+                    2 => Msaa::Sample2,
+                    8 => Msaa::Sample8,
+                    _ => Msaa::Sample4,
+                },
+                tonemapping: match custom.tonemapping {
+                    Tonemap::None => Tonemapping::None,
+                    Tonemap::Reinhard => Tonemapping::Reinhard,
+                    Tonemap::AcesFitted => Tonemapping::AcesFitted,
+                    Tonemap::Agx => Tonemapping::AgX,
+                    Tonemap::TonyMcMapface => Tonemapping::TonyMcMapface,
+                    Tonemap::BlenderFilmic => Tonemapping::BlenderFilmic,
+                },
+            },
+        }
+    }
+}
+
+/// applies the quality preset to cameras and lights as they're spawned or the preset changes.
+/// the sphere reads `Quality` itself.
+pub struct QualityPlugin;
+
+impl Plugin for QualityPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Config>()
+            .init_resource::<SelectedQuality>()
+            .init_resource::<Quality>()
+            .init_resource::<PointLightShadowMap>()
+            .add_systems(
+                Update,
+                (
+                    select_quality.run_if(resource_changed::<Config>),
+                    resolve_quality,
+                    (apply_camera_quality, apply_light_quality),
+                )
+                    .chain(),
+            );
+    }
+}
+
+/// switches presets when the config's choice of preset changes. a preset picked with bg-cli
+/// survives unrelated config edits.
+fn select_quality(
+    config: Res<Config>,
+    mut selected: ResMut<SelectedQuality>,
+    mut from_config: Local<Option<QualityPreset>>,
+) {
+    if *from_config != Some(config.quality.preset) {
+        selected.set_if_neq(SelectedQuality(config.quality.preset));
+        *from_config = Some(config.quality.preset);
+    }
+}
+
+fn resolve_quality(
+    config: Res<Config>,
+    selected: Res<SelectedQuality>,
+    mut quality: ResMut<Quality>,
+) {
+    if !(selected.is_changed() || config.is_changed()) {
+        return;
+    }
+
+    if quality.set_if_neq(Quality::of(**selected, &config.quality)) {
+        info!("quality {}: {:?}", **selected, *quality);
+    }
+}
+
+fn apply_camera_quality(
+    mut cmds: Commands,
+    quality: Res<Quality>,
+    cameras: Query<(Entity, Ref<Camera3d>)>,
+) {
+    for (entity, camera) in &cameras {
+        if quality.is_changed() || camera.is_added() {
+            cmds.entity(entity)
+                .insert((quality.msaa, quality.tonemapping));
+        }
+    }
+}
+
+fn apply_light_quality(
+    quality: Res<Quality>,
+    mut lights: Query<&mut PointLight>,
+    mut shadow_map: ResMut<PointLightShadowMap>,
+) {
+    for mut light in &mut lights {
+        if quality.is_changed() || light.is_added() {
+            light.shadows_enabled = quality.shadows;
+        }
+    }
+
+    if quality.is_changed() {
+        shadow_map.size = quality.shadow_map_size;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn app() -> App {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, QualityPlugin));
+        app.world_mut().spawn(Camera3d::default());
+        app.world_mut().spawn(PointLight::default());
+        app.update();
+
+        app
+    }
+
+    /// what the camera, light and shadow map ended up with.
+    fn applied(app: &mut App) -> (Msaa, Tonemapping, bool, usize) {
+        let world = app.world_mut();
+        let (msaa, tonemapping) = world
+            .query_filtered::<(&Msaa, &Tonemapping), With<Camera3d>>()
+            .single(world);
+        let (msaa, tonemapping) = (*msaa, *tonemapping);
+        let shadows = world.query::<&PointLight>().single(world).shadows_enabled;
+
+        (
+            msaa,
+            tonemapping,
+            shadows,
+            world.resource::<PointLightShadowMap>().size,
+        )
+    }
+
+    #[test]
+    fn switching_presets_updates_cameras_and_lights() {
+        let mut app = app();
+
+        assert_eq!(
+            applied(&mut app),
+            (Msaa::Sample4, Tonemapping::TonyMcMapface, true, 1024)
+        );
+
+        app.insert_resource(SelectedQuality(QualityPreset::Low));
+        app.update();
+
+        assert_eq!(
+            applied(&mut app),
+            (Msaa::Off, Tonemapping::None, false, 512)
+        );
+
+        app.insert_resource(Config {
+            quality: QualityConfig {
+                preset: QualityPreset::Custom,
+                msaa: 8,
+                tonemapping: Tonemap::Agx,
+                shadow_map_size: 2048,
+                ..default()
+            },
+            ..default()
+        });
+        app.update();
+
+        assert_eq!(
+            applied(&mut app),
+            (Msaa::Sample8, Tonemapping::AgX, true, 2048)
+        );
+    }
+
+    #[test]
+    fn a_picked_preset_survives_unrelated_config_changes() {
+        let mut app = app();
+
+        app.insert_resource(SelectedQuality(QualityPreset::High));
+        app.update();
+        app.world_mut().resource_mut::<Config>().sphere.period = 30.0;
+        app.update();
+
+        assert_eq!(
+            **app.world().resource::<SelectedQuality>(),
+            QualityPreset::High
+        );
+        assert_eq!(app.world().resource::<PointLightShadowMap>().size, 4096);
+
+        app.world_mut().resource_mut::<Config>().quality.preset = QualityPreset::Low;
+        app.update();
+
+        assert_eq!(
+            **app.world().resource::<SelectedQuality>(),
+            QualityPreset::Low
+        );
+        assert_eq!(app.world().resource::<PointLightShadowMap>().size, 512);
+    }
+}
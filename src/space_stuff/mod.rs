@@ -1,13 +1,90 @@
-use crate::{Mode, Shape};
+use crate::{
+    Mode, Shape, WallpaperRng,
+    clock::Resumed,
+    config::Config,
+    outputs::{ActiveModes, ModeEntity, ModeView, ModeViews, OutputCamera, in_mode},
+};
 use bevy::{
-    asset::RenderAssetUsages,
+    asset::{AssetLoadFailedEvent, RenderAssetUsages},
+    ecs::system::SystemParam,
     prelude::*,
-    render::render_resource::{Extent3d, TextureDimension, TextureFormat},
+    render::{
+        camera::CameraProjection,
+        primitives::Frustum,
+        render_resource::{Extent3d, TextureDimension, TextureFormat},
+    },
+    time::common_conditions::paused,
+};
+use sighting::{FadeOut, Sighting, View, ViewSize};
+use space_objects::{
+    SpaceThing, SpaceThingTrait,
+    catalog::{Catalog, CatalogLoader},
+    cataloged::Cataloged,
+    satellite::{self, Satellite},
 };
-use space_objects::{SpaceThing, SpaceThingTrait, asteroid::Asteroid, astronaut::Astronaut};
-use std::f32::consts::PI;
+use spawn::{SpawnRule, SpawnScheduler, SpawnTable};
+use std::{collections::HashMap, f32::consts::PI};
 
+pub mod sighting;
 pub mod space_objects;
+pub mod spawn;
+
+/// where the camera filming space stuff sits, looking down at the middle of the scene.
+pub const CAMERA_Z: f32 = 8.0;
+
+/// the low power frame rate is rounded up to a multiple of this, so it doesn't change every frame
+/// as things speed up.
+const FPS_STEP: f32 = 5.0;
+
+/// kinds of space things that are made in code rather than the catalog.
+const BUILT_IN: [(&str, SpawnRule); 1] = [(
+    Satellite::NAME,
+    SpawnRule {
+        weight: 0.5,
+        max_alive: Some(2),
+        one_in: None,
+    },
+)];
+
+/// how fast the quickest space thing moves across the view, counting how fast it seems to grow
+/// and spin, in units per second at a distance of 1 from the camera.
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq)]
+struct FastestThing(f32);
+
+/// the catalog in use, from `SpaceStuffConfig::catalog`.
+#[derive(Resource, Clone, Debug, Default, Deref)]
+pub struct SpaceCatalog(pub Handle<Catalog>);
+
+/// the catalog in use, once it's loaded, and the config's changes to it.
+#[derive(SystemParam)]
+pub struct CurrentCatalog<'w> {
+    handle: Res<'w, SpaceCatalog>,
+    catalogs: Res<'w, Assets<Catalog>>,
+    config: Res<'w, Config>,
+}
+
+impl CurrentCatalog<'_> {
+    pub fn get(&self) -> Option<&Catalog> {
+        self.catalogs.get(&self.handle.0)
+    }
+
+    pub fn spawn_table(&self) -> Option<SpawnTable> {
+        self.get()
+            .map(|catalog| SpawnTable::new(&BUILT_IN, catalog, &self.config.space_stuff.kinds))
+    }
+}
+
+/// exists while at least one output shows space stuff.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct SpaceStuffShown;
+
+impl ComputedStates for SpaceStuffShown {
+    type SourceStates = ActiveModes;
+
+    fn compute(active: ActiveModes) -> Option<Self> {
+        active.0.contains(&Mode::SpaceStuff).then_some(Self)
+    }
+}
 
 #[derive(Default)]
 pub struct SpaceStuff;
@@ -18,22 +95,104 @@ impl Plugin for SpaceStuff {
         // wp_plug.run_on_any_thread = true;
 
         // App::new()
-        app.add_systems(OnEnter(Mode::SpaceStuff), camera_setup)
-            .add_systems(OnExit(Mode::SpaceStuff), camera_teardown)
+        app.init_resource::<Config>()
+            .init_resource::<WallpaperRng>()
+            .init_resource::<ModeViews>()
+            .init_resource::<SpaceCatalog>()
+            .init_resource::<SpawnScheduler>()
+            .init_resource::<FastestThing>()
+            .init_asset::<Catalog>()
+            .register_asset_loader(CatalogLoader)
+            .init_state::<ActiveModes>()
+            .add_computed_state::<SpaceStuffShown>()
+            .add_event::<Resumed>()
+            .add_systems(OnEnter(SpaceStuffShown), (light_setup, restart_schedule))
+            .add_systems(OnExit(SpaceStuffShown), scene_teardown)
             .add_systems(
                 Update,
                 (
-                    mod_spacething_transform,
-                    despawn_spacethings,
-                    spawn_spacething.run_if(time_to_spawn),
+                    load_catalog.run_if(resource_changed::<Config>),
+                    update_view
+                        .run_if(resource_changed::<Config>.or(resource_changed::<FastestThing>)),
+                    report_catalog,
+                ),
+            )
+            .add_systems(
+                Update,
+                (
+                    (
+                        mod_spacething_transform,
+                        sight_spacethings,
+                        despawn_spacethings,
+                        fade_spacethings,
+                    )
+                        .chain(),
+                    satellite::track_light,
+                    (
+                        (clear_spacethings, restart_schedule)
+                            .chain()
+                            .run_if(on_event::<Resumed>),
+                        schedule_spawns.run_if(not(paused)),
+                        spawn_spacethings,
+                    )
+                        .chain(),
                     // log_assets,
                 )
-                    .run_if(in_state(Mode::SpaceStuff)),
+                    .run_if(in_state(SpaceStuffShown)),
             );
+
+        app.world_mut()
+            .resource_mut::<ModeViews>()
+            .insert(Mode::SpaceStuff, camera_view(&Config::default(), 0.0));
+    }
+}
+
+/// how space stuff is filmed, every output showing it gets a camera set up like this. things
+/// come straight at the camera and speed up across the view as they near it, so the frame rate
+/// follows the `fastest` one there is right now.
+fn camera_view(config: &Config, fastest: f32) -> ModeView {
+    let mut view = ModeView {
+        transform: Transform::from_xyz(0.0, 0.0, CAMERA_Z)
+            .looking_at(Vec3::new(0.0, 0.0, 0.0), Vec3::Y),
+        projection: PerspectiveProjection {
+            // far: 1_000.0,
+            far: 1_000_000.0,
+            ..default()
+        },
+        fps: 0.0,
+    };
+    let needed = (view.smooth_fps(fastest, 1.0) / FPS_STEP).ceil() * FPS_STEP;
+    view.fps = needed.max(config.space_stuff.low_power_fps);
+
+    view
+}
+
+fn update_view(config: Res<Config>, fastest: Res<FastestThing>, mut views: ResMut<ModeViews>) {
+    let view = camera_view(&config, fastest.0);
+
+    if views.get(&Mode::SpaceStuff).map(|view| view.fps) != Some(view.fps) {
+        views.insert(Mode::SpaceStuff, view);
     }
 }
 
-fn camera_setup(
+/// how fast something of `radius` going from `from` to `to` in `secs` seems to move across the
+/// view, grow and spin, in units per second at a distance of 1 from the camera.
+fn view_speed(from: &Transform, to: &Transform, radius: f32, secs: f32) -> f32 {
+    let seen = |transform: &Transform| {
+        let distance = CAMERA_Z - transform.translation.z;
+
+        (distance > 0.0).then(|| (transform.translation.xy() / distance, radius / distance))
+    };
+    let (Some((from_at, from_size)), Some((to_at, to_size))) = (seen(from), seen(to)) else {
+        return 0.0;
+    };
+    // a spinning thing's edge goes around at its spin times its size.
+    let spin = from.rotation.angle_between(to.rotation) * to_size;
+
+    (from_at.distance(to_at) + (to_size - from_size).abs() + spin) / secs
+}
+
+fn light_setup(
     mut commands: Commands,
     // mut materials: ResMut<Assets<StandardMaterial>>,
     // mut images: ResMut<Assets<Image>>,
@@ -64,17 +223,6 @@ fn camera_setup(
 
     // commands.spawn(DebugTexture(debug_material));
 
-    commands.spawn((
-        Camera3d::default(),
-        Transform::from_xyz(0.0, 0.0, 8.0).looking_at(Vec3::new(0.0, 0.0, 0.0), Vec3::Y),
-        Camera::default(),
-        Projection::Perspective(PerspectiveProjection {
-            // far: 1_000.0,
-            far: 1_000_000.0,
-            ..default()
-        }),
-    ));
-
     let intensity = 10_000_000.0;
     let light = PointLight {
         shadows_enabled: true,
@@ -90,78 +238,272 @@ fn camera_setup(
         light,
         // Transform::from_xyz(8.0, 16.0, 8.0),
         Transform::from_xyz(1.0, 1.0, 8.0).looking_at(Vec3::new(1.0, 1.0, 0.0), Vec3::Y),
+        in_mode(Mode::SpaceStuff),
     ));
 
     commands.spawn((
         light,
         // Transform::from_xyz(8.0, 16.0, 8.0),
         Transform::from_xyz(-1.0, 1.0, 8.0).looking_at(Vec3::new(-1.0, 1.0, 0.0), Vec3::Y),
+        in_mode(Mode::SpaceStuff),
     ));
 
     commands.spawn((
         light,
         // Transform::from_xyz(8.0, 16.0, 8.0),
         Transform::from_xyz(1.0, -1.0, 8.0).looking_at(Vec3::new(1.0, -1.0, 0.0), Vec3::Y),
+        in_mode(Mode::SpaceStuff),
     ));
 
     commands.spawn((
         light,
         // Transform::from_xyz(8.0, 16.0, 8.0),
         Transform::from_xyz(-1.0, -1.0, 8.0).looking_at(Vec3::new(-1.0, -1.0, 0.0), Vec3::Y),
+        in_mode(Mode::SpaceStuff),
     ));
 }
 
-fn camera_teardown(
-    mut cmds: Commands,
-    mut cameras: Query<Entity, With<Camera>>,
-    mut lights: Query<Entity, With<PointLight>>,
+fn scene_teardown(mut cmds: Commands, entities: Query<(Entity, &ModeEntity)>) {
+    for (entity, ModeEntity(mode)) in entities.iter() {
+        if *mode == Mode::SpaceStuff {
+            cmds.entity(entity).despawn_recursive()
+        }
+    }
+}
+
+/// loads the catalog the config names, again whenever that changes.
+fn load_catalog(
+    config: Res<Config>,
+    asset_server: Res<AssetServer>,
+    mut catalog: ResMut<SpaceCatalog>,
+    mut from_config: Local<Option<String>>,
 ) {
-    for camera in cameras.iter() {
-        cmds.entity(camera).despawn_recursive()
+    if from_config.as_ref() != Some(&config.space_stuff.catalog) {
+        catalog.0 = asset_server.load(&config.space_stuff.catalog);
+        *from_config = Some(config.space_stuff.catalog.clone());
+    }
+}
+
+fn report_catalog(
+    mut events: EventReader<AssetEvent<Catalog>>,
+    mut failures: EventReader<AssetLoadFailedEvent<Catalog>>,
+    catalogs: Res<Assets<Catalog>>,
+) {
+    for event in events.read() {
+        if let AssetEvent::LoadedWithDependencies { id } | AssetEvent::Modified { id } = event
+            && let Some(catalog) = catalogs.get(*id)
+        {
+            info!(
+                "space things: {}",
+                catalog.kinds.keys().cloned().collect::<Vec<_>>().join(", ")
+            );
+        }
     }
 
-    for light in lights.iter() {
-        cmds.entity(light).despawn_recursive()
+    for failure in failures.read() {
+        warn!("couldn't load {}: {}", failure.path, failure.error);
     }
 }
 
-fn spawn_spacething(
-    cmds: Commands,
+fn schedule_spawns(
+    time: Res<Time>,
+    config: Res<Config>,
+    mut scheduler: ResMut<SpawnScheduler>,
+    mut rng: ResMut<WallpaperRng>,
+    space_things: Query<(), With<SpaceThing>>,
+) {
+    scheduler.tick(
+        &config.space_stuff,
+        time.delta_secs(),
+        space_things.iter().count(),
+        &mut rng.0,
+    );
+}
+
+fn restart_schedule(mut scheduler: ResMut<SpawnScheduler>) {
+    scheduler.restart();
+}
+
+fn spawn_spacethings(
+    mut cmds: Commands,
     // mut meshes: ResMut<Assets<Mesh>>,
     // debug_material: Single<&DebugTexture>,
     mut materials: ResMut<Assets<StandardMaterial>>,
     asset_server: Res<AssetServer>,
+    mut rng: ResMut<WallpaperRng>,
+    catalog: CurrentCatalog,
+    mut scheduler: ResMut<SpawnScheduler>,
+    scene: SpaceScene,
 ) {
     // let sphere = meshes.add(Sphere::default());
 
-    // let mut space_thing = SpaceThing::Asteroid(Asteroid::default());
-    let mut space_thing = SpaceThing::Astronaut(Astronaut::default());
+    // nothing to spawn until the catalog has loaded, spawns due before then are dropped.
+    let (Some(catalog), Some(table)) = (catalog.get(), catalog.spawn_table()) else {
+        scheduler.take();
+        return;
+    };
+    let mut alive = scene.alive();
+
+    for _ in 0..scheduler.take() {
+        let name = match table.pick(&alive, &mut rng.0) {
+            Ok(name) => name,
+            Err(why) => {
+                debug!("nothing to spawn, {why}");
+                return;
+            }
+        };
+        // anything not in the catalog is built in.
+        let mut space_thing = match catalog.kinds.get(name) {
+            Some(kind) => SpaceThing::Cataloged(Cataloged::new(name, kind, &mut rng.0)),
+            None => SpaceThing::Satellite(Satellite::new(scene.view_size(), &mut rng.0)),
+        };
+
+        *alive.entry(name).or_default() += 1;
+
+        // commands
+        //     .spawn(space_thing.spawn_model(&asset_server, &mut materials, 1_000_000.0))
+        //     .insert(space_thing);
+        // let id = cmds.spawn(space_thing).id();
+        space_thing.spawn_model(cmds.reborrow(), &asset_server, &mut materials, 1_000_000.0);
+
+        // info!("spawning spacething");
+        debug!("spawning spacething");
+    }
+}
+
+/// the space things there are and the cameras filming them.
+#[derive(SystemParam)]
+struct SpaceScene<'w, 's> {
+    space_things: Query<'w, 's, &'static SpaceThing>,
+    cameras: Query<'w, 's, (&'static OutputCamera, &'static Projection)>,
+}
+
+impl SpaceScene<'_, '_> {
+    /// how many of each kind there are.
+    fn alive(&self) -> HashMap<&str, usize> {
+        let mut alive = HashMap::new();
+
+        for space_thing in &self.space_things {
+            *alive.entry(space_thing.kind()).or_default() += 1;
+        }
 
-    // commands
-    //     .spawn(space_thing.spawn_model(&asset_server, &mut materials, 1_000_000.0))
-    //     .insert(space_thing);
-    // let id = cmds.spawn(space_thing).id();
-    space_thing.spawn_model(cmds, &asset_server, &mut materials, 1_000_000.0);
+        alive
+    }
 
-    // info!("spawning spacething");
-    debug!("spawning spacething");
+    /// how much the cameras take in between them.
+    fn view_size(&self) -> ViewSize {
+        self.cameras
+            .iter()
+            .filter(|(camera, _)| camera.mode == Mode::SpaceStuff)
+            .filter_map(|(camera, projection)| match projection {
+                Projection::Perspective(perspective) => {
+                    Some(ViewSize::of(perspective, camera.sub_view))
+                }
+                Projection::Orthographic(_) => None,
+            })
+            .reduce(ViewSize::max)
+            .unwrap_or_default()
+    }
 }
 
 fn mod_spacething_transform(
     mut query: Query<(&mut SpaceThing, &mut Transform), With<Shape>>,
     time: Res<Time>,
+    mut fastest: ResMut<FastestThing>,
 ) {
+    let mut speed = 0.0_f32;
+
     for (mut space_thing, mut transform) in &mut query {
+        let before = *transform;
         space_thing.update_orientation(&time, &mut transform);
         space_thing.update_location(&time, &mut transform);
+        speed = speed.max(view_speed(
+            &before,
+            &transform,
+            space_thing.radius(),
+            time.delta_secs(),
+        ));
         // info!("space thing location = {}", transform.translation);
         debug!("space thing location = {}", transform.translation);
     }
+
+    // nothing moved while paused, keep what it was for when it carries on.
+    if time.delta_secs() > 0.0 {
+        fastest.set_if_neq(FastestThing(speed));
+    }
 }
 
-fn despawn_spacethings(mut cmds: Commands, space_things: Query<(&SpaceThing, &Transform, Entity)>) {
-    for (space_thing, transform, entity) in space_things.iter() {
-        if space_thing.should_despawn() || transform.translation[2] > 8.0 {
+/// works out where each space thing is relative to the cameras filming space stuff.
+fn sight_spacethings(
+    cameras: Query<(&OutputCamera, &Frustum, &GlobalTransform, &Projection)>,
+    mut space_things: Query<(&SpaceThing, &Transform, &mut Sighting)>,
+) {
+    let views: Vec<_> = cameras
+        .iter()
+        .filter(|(camera, ..)| camera.mode == Mode::SpaceStuff)
+        .map(|(camera, frustum, transform, projection)| View {
+            frustum: whole_frustum(camera, frustum, transform, projection),
+            position: transform.translation(),
+            far: match projection {
+                Projection::Perspective(perspective) => perspective.far,
+                Projection::Orthographic(orthographic) => orthographic.far,
+            },
+        })
+        .collect();
+
+    // without a camera nothing's in view, and everything would look lost.
+    if views.is_empty() {
+        return;
+    }
+
+    for (space_thing, transform, mut sighting) in &mut space_things {
+        sighting.update(transform.translation, space_thing.radius(), &views);
+    }
+}
+
+/// what a camera films, or for one showing its part of a span, what the whole span films. going
+/// behind a bezel takes a thing out of every output's part, but not out of the span.
+fn whole_frustum(
+    camera: &OutputCamera,
+    frustum: &Frustum,
+    transform: &GlobalTransform,
+    projection: &Projection,
+) -> Frustum {
+    // bevy works the frustum out from the output's own size, not the span's.
+    let (Some(sub_view), Projection::Perspective(perspective)) = (camera.sub_view, projection)
+    else {
+        return *frustum;
+    };
+    let whole = PerspectiveProjection {
+        aspect_ratio: sub_view.full_size.x as f32 / sub_view.full_size.y as f32,
+        ..perspective.clone()
+    };
+
+    whole.compute_frustum(transform)
+}
+
+fn despawn_spacethings(
+    mut cmds: Commands,
+    config: Res<Config>,
+    space_things: Query<(Entity, &SpaceThing, &Sighting, &Transform), Without<FadeOut>>,
+) {
+    let config = &config.space_stuff;
+
+    for (entity, space_thing, sighting, transform) in space_things.iter() {
+        let too_close = sighting.in_view && sighting.distance < config.despawn_near;
+
+        if !(too_close || space_thing.should_despawn(sighting)) {
+            continue;
+        }
+
+        // out of view it can go at once, nobody sees it vanish.
+        if sighting.in_view && config.despawn_fade > 0.0 {
+            cmds.entity(entity).insert(FadeOut {
+                timer: Timer::from_seconds(config.despawn_fade, TimerMode::Once),
+                scale: transform.scale,
+            });
+            debug!("fading out spacething");
+        } else {
             cmds.entity(entity).despawn_recursive();
             // info!("despawning spacething");
             debug!("despawning spacething");
@@ -169,9 +511,29 @@ fn despawn_spacethings(mut cmds: Commands, space_things: Query<(&SpaceThing, &Tr
     }
 }
 
-fn time_to_spawn(time: Res<Time>) -> bool {
-    // info!("{}", time.elapsed_secs() % 3.0);
-    time.elapsed_secs() % 2.5 <= 0.0303
+fn fade_spacethings(
+    mut cmds: Commands,
+    time: Res<Time>,
+    mut fading: Query<(Entity, &mut FadeOut, &mut Transform)>,
+) {
+    for (entity, mut fade, mut transform) in &mut fading {
+        fade.timer.tick(time.delta());
+        transform.scale = fade.scale * fade.timer.fraction_remaining();
+
+        if fade.timer.finished() {
+            cmds.entity(entity).despawn_recursive();
+            debug!("despawning spacething");
+        }
+    }
+}
+
+/// starts over with a fresh field after a suspend, instead of whatever was left mid flight.
+fn clear_spacethings(mut cmds: Commands, space_things: Query<Entity, With<SpaceThing>>) {
+    for entity in space_things.iter() {
+        cmds.entity(entity).despawn_recursive();
+    }
+
+    debug!("cleared space things after resuming");
 }
 
 // /// Creates a colorful test pattern
@@ -214,3 +576,190 @@ fn time_to_spawn(time: Res<Time>) -> bool {
 //         // }
 //     }
 // }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::outputs::span_sub_view;
+    use bevy::{render::camera::SubCameraView, time::TimeUpdateStrategy};
+    use rand::{SeedableRng, rngs::StdRng};
+    use space_objects::catalog::ThingKind;
+    use std::time::Duration;
+
+    /// how far things spawn from the middle of the view, as for the wallpaper.
+    const FOV: f32 = 1_000_000.0;
+
+    /// an app that sights and despawns space things filmed by a camera for each of `sub_views`.
+    fn app(sub_views: &[Option<SubCameraView>]) -> App {
+        let mut app = App::new();
+
+        app.add_plugins(MinimalPlugins)
+            .init_resource::<Config>()
+            .init_resource::<FastestThing>()
+            .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+                100,
+            )))
+            .add_systems(
+                Update,
+                (sight_spacethings, despawn_spacethings, fade_spacethings).chain(),
+            );
+
+        let view = camera_view(&Config::default(), 0.0);
+        let transform = GlobalTransform::from(view.transform);
+
+        for sub_view in sub_views {
+            // the way bevy sets the camera up for a 16:9 window.
+            let projection = PerspectiveProjection {
+                aspect_ratio: 16.0 / 9.0,
+                ..view.projection.clone()
+            };
+
+            app.world_mut().spawn((
+                OutputCamera {
+                    window: Entity::PLACEHOLDER,
+                    mode: Mode::SpaceStuff,
+                    yaw: 0.0,
+                    sub_view: *sub_view,
+                },
+                projection.compute_frustum(&transform),
+                transform,
+                Projection::Perspective(projection),
+            ));
+        }
+
+        app
+    }
+
+    fn spawn_thing(app: &mut App, rng: &mut StdRng) -> Entity {
+        let mut thing = Cataloged::new("asteroid", &default(), rng);
+        let transform = thing.get_transform(FOV);
+
+        app.world_mut()
+            .spawn((SpaceThing::Cataloged(thing), transform, Shape))
+            .id()
+    }
+
+    /// puts `thing` `x` across from the middle of the view, as far away as things spawn.
+    fn move_thing(app: &mut App, thing: Entity, x: f32) {
+        let mut transform = app.world_mut().get_mut::<Transform>(thing).unwrap();
+        transform.translation.x = x;
+        app.update();
+    }
+
+    fn exists(app: &App, thing: Entity) -> bool {
+        app.world().get_entity(thing).is_ok()
+    }
+
+    /// half the width a 16:9 view sees at the distance things spawn at.
+    fn half_width() -> f32 {
+        let view = camera_view(&Config::default(), 0.0);
+        let depth = FOV + view.transform.translation.z;
+
+        depth * (view.projection.fov / 2.0).tan() * 16.0 / 9.0
+    }
+
+    #[test]
+    fn things_that_move_out_of_view_are_despawned() {
+        let mut app = app(&[None]);
+        let thing = spawn_thing(&mut app, &mut StdRng::seed_from_u64(1));
+
+        move_thing(&mut app, thing, 0.0);
+        assert!(app.world().get::<Sighting>(thing).unwrap().seen);
+
+        move_thing(&mut app, thing, half_width() * 0.9);
+        assert!(exists(&app, thing));
+
+        move_thing(&mut app, thing, half_width() * 1.5);
+        assert!(!exists(&app, thing));
+    }
+
+    #[test]
+    fn things_stay_while_anywhere_in_a_span() {
+        // two 16:9 monitors side by side, with 20mm of bezel between their pictures.
+        let left = Rect::new(0.0, 0.0, 527.0, 296.0);
+        let right = Rect::new(547.0, 0.0, 1074.0, 296.0);
+        let span = [left, right];
+        let mut app = app(&[
+            Some(span_sub_view(left, &span)),
+            Some(span_sub_view(right, &span)),
+        ]);
+        let thing = spawn_thing(&mut app, &mut StdRng::seed_from_u64(1));
+
+        // behind the bezel, then where only the right monitor sees, well past a single screen.
+        for x in [0.0, half_width() * 1.5, -half_width() * 1.5] {
+            move_thing(&mut app, thing, x);
+            assert!(exists(&app, thing), "{x}");
+        }
+
+        // past the edge of both monitors.
+        move_thing(&mut app, thing, half_width() * 2.5);
+        assert!(!exists(&app, thing));
+    }
+
+    #[test]
+    fn things_dont_pile_up() {
+        let mut app = app(&[None]);
+        let mut rng = StdRng::seed_from_u64(2);
+        let mut most = 0;
+
+        app.add_systems(Update, mod_spacething_transform.before(sight_spacethings));
+
+        // one a second for ten minutes.
+        for frame in 0..6000 {
+            if frame % 10 == 0 {
+                spawn_thing(&mut app, &mut rng);
+            }
+
+            app.update();
+
+            let alive = app
+                .world_mut()
+                .query::<&SpaceThing>()
+                .iter(app.world())
+                .count();
+            most = most.max(alive);
+        }
+
+        assert!(most <= 30, "{most}");
+    }
+
+    #[test]
+    fn low_power_frame_rate_follows_the_fastest_thing() {
+        let mut app = app(&[None]);
+        let kind = ThingKind {
+            speed: [0.2, 0.2],
+            ..default()
+        };
+        let mut thing = Cataloged::new("asteroid", &kind, &mut StdRng::seed_from_u64(1));
+        let transform = thing.get_transform(FOV);
+        let thing = app
+            .world_mut()
+            .spawn((SpaceThing::Cataloged(thing), transform, Shape))
+            .id();
+        let fps = |app: &App| app.world().resource::<ModeViews>()[&Mode::SpaceStuff].fps;
+        let mut seen = Vec::new();
+
+        app.init_resource::<ModeViews>().add_systems(
+            Update,
+            (mod_spacething_transform, update_view)
+                .chain()
+                .before(sight_spacethings),
+        );
+
+        while exists(&app, thing) {
+            app.update();
+            seen.push(fps(&app));
+        }
+
+        // far off it hardly moves, and it rushes past at the end.
+        let floor = Config::default().space_stuff.low_power_fps;
+
+        assert_eq!(seen[0], floor);
+        assert!(seen.is_sorted(), "{seen:?}");
+        assert!(seen.iter().all(|fps| fps % FPS_STEP == 0.0), "{seen:?}");
+        assert!(seen[seen.len() - 2] > 100.0, "{seen:?}");
+
+        app.update();
+        assert_eq!(fps(&app), floor);
+    }
+}
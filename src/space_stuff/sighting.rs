@@ -0,0 +1,124 @@
+use bevy::{
+    math::Vec3A,
+    prelude::*,
+    render::{
+        camera::SubCameraView,
+        primitives::{Frustum, Sphere},
+    },
+};
+
+/// what a camera filming space stuff can see.
+#[derive(Clone, Copy, Debug)]
+pub struct View {
+    pub frustum: Frustum,
+    pub position: Vec3,
+    /// how far it sees.
+    pub far: f32,
+}
+
+/// how far the cameras see to either side and up and down, per unit of depth in front of them.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ViewSize {
+    pub half_width: f32,
+    pub half_height: f32,
+}
+
+impl Default for ViewSize {
+    /// bevy's default camera on a 16:9 screen.
+    fn default() -> Self {
+        Self::of(
+            &PerspectiveProjection {
+                aspect_ratio: 16.0 / 9.0,
+                ..default()
+            },
+            None,
+        )
+    }
+}
+
+impl ViewSize {
+    /// what `projection` takes in, all of the span for an output showing part of one.
+    pub fn of(projection: &PerspectiveProjection, sub_view: Option<SubCameraView>) -> Self {
+        let aspect_ratio = sub_view.map_or(projection.aspect_ratio, |sub_view| {
+            sub_view.full_size.x as f32 / sub_view.full_size.y as f32
+        });
+        let half_height = (projection.fov / 2.0).tan();
+
+        Self {
+            half_width: half_height * aspect_ratio,
+            half_height,
+        }
+    }
+
+    /// whether any of a thing reaching `radius` from `offset`, measured from a camera looking
+    /// down -z, is in view.
+    pub fn contains(&self, offset: Vec3, radius: f32) -> bool {
+        let depth = -offset.z;
+        // how far the thing's center has to be past a side for none of it to show.
+        let past = |half: f32| radius * (1.0 + half * half).sqrt();
+
+        depth + radius > 0.0
+            && offset.x.abs() - past(self.half_width) < depth * self.half_width
+            && offset.y.abs() - past(self.half_height) < depth * self.half_height
+    }
+
+    /// big enough for both.
+    pub fn max(self, other: Self) -> Self {
+        Self {
+            half_width: self.half_width.max(other.half_width),
+            half_height: self.half_height.max(other.half_height),
+        }
+    }
+}
+
+/// where a space thing is relative to the cameras filming it, kept up to date every frame.
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq)]
+pub struct Sighting {
+    /// some part of it is inside a camera's view.
+    pub in_view: bool,
+    /// it's been in view since it spawned.
+    pub seen: bool,
+    /// how far the nearest camera is from its edge.
+    pub distance: f32,
+    /// how far the farthest seeing camera sees.
+    pub far: f32,
+}
+
+impl Sighting {
+    /// looks for a thing reaching `radius` from `center` in `views`.
+    pub fn update(&mut self, center: Vec3, radius: f32, views: &[View]) {
+        let sphere = Sphere {
+            center: Vec3A::from(center),
+            radius,
+        };
+
+        self.in_view = views
+            .iter()
+            .any(|view| view.frustum.intersects_sphere(&sphere, true));
+        self.seen |= self.in_view;
+        self.distance = views
+            .iter()
+            .map(|view| view.position.distance(center) - radius)
+            .fold(f32::INFINITY, f32::min);
+        self.far = views.iter().map(|view| view.far).fold(0.0, f32::max);
+    }
+
+    /// it was in view and isn't anymore.
+    pub fn left_view(&self) -> bool {
+        self.seen && !self.in_view
+    }
+
+    /// it never came into view and is now well beyond where any camera could see it, so it
+    /// never will.
+    pub fn lost(&self) -> bool {
+        !self.seen && self.distance > self.far * 2.0
+    }
+}
+
+/// shrinks a space thing away before it's despawned.
+#[derive(Component, Clone, Debug)]
+pub struct FadeOut {
+    pub timer: Timer,
+    /// its size before it started shrinking.
+    pub scale: Vec3,
+}
@@ -0,0 +1,336 @@
+use crate::config::HexColor;
+use bevy::{
+    asset::{AssetLoader, LoadContext, io::Reader},
+    prelude::*,
+};
+use serde::Deserialize;
+use std::{collections::BTreeMap, fmt, io, ops::RangeInclusive, str};
+
+/// every kind of space thing there is, keyed by name. loaded from a `.catalog.toml` asset, one
+/// table per kind.
+#[derive(Asset, TypePath, Clone, Debug, Default, Deserialize)]
+#[serde(transparent)]
+pub struct Catalog {
+    pub kinds: BTreeMap<String, ThingKind>,
+}
+
+/// what one kind of space thing looks like and how it moves.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ThingKind {
+    /// a gltf file and the label of the scene or mesh in it, e.g.
+    /// `"models/asteroid/model.gltf#Mesh0/Primitive0"`. `#Scene0` without a label.
+    pub model: String,
+    /// replaces the model's own materials when set.
+    pub material: Option<MaterialOverride>,
+    /// the smallest and largest size, in meters. default: `[0.1, 0.24]`
+    pub size: [f32; 2],
+    /// default: `[0.05, 0.15]`
+    pub speed: [f32; 2],
+    /// how fast it spins. default: `[0.0, 0.25]`
+    pub rotation_speed: [f32; 2],
+    /// how far the model reaches from its origin before it's scaled, for telling when it's out
+    /// of view. default: `1.5`
+    pub radius: f32,
+    /// how far from the middle of the view it heads, in multiples of the distance it spawns at.
+    /// default: `0.5`
+    pub spread: f32,
+    pub trajectory: Trajectory,
+    /// how often it spawns compared to the other kinds. default: `1.0`
+    pub weight: f32,
+    /// how many can be on screen at once, no limit when unset.
+    pub max_alive: Option<u32>,
+    /// makes it a rare event: each spawn has a one in this many chance of being one, instead of
+    /// it being picked by `weight`.
+    pub one_in: Option<u32>,
+}
+
+impl Default for ThingKind {
+    fn default() -> Self {
+        Self {
+            model: String::new(),
+            material: None,
+            size: [0.1, 0.24],
+            speed: [0.05, 0.15],
+            rotation_speed: [0.0, 0.25],
+            radius: 1.5,
+            spread: 0.5,
+            trajectory: Trajectory::Straight,
+            weight: 1.0,
+            max_alive: None,
+            one_in: None,
+        }
+    }
+}
+
+/// the path a space thing takes from where it spawns, far in front of the camera.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Trajectory {
+    /// straight out towards the viewer.
+    #[default]
+    Straight,
+    /// curving around the middle of the view, turning by this many radians on the way.
+    Arc(f32),
+}
+
+/// changes to the material a space thing is drawn with, anything left out keeps bevy's default.
+#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MaterialOverride {
+    /// an image, relative to the assets directory.
+    pub texture: Option<String>,
+    pub color: Option<HexColor>,
+    pub emissive: Option<HexColor>,
+    pub roughness: Option<f32>,
+    pub metallic: Option<f32>,
+}
+
+impl MaterialOverride {
+    pub fn material(&self, asset_server: &AssetServer) -> StandardMaterial {
+        let mut material = StandardMaterial {
+            base_color_texture: self.texture.as_ref().map(|path| asset_server.load(path)),
+            ..default()
+        };
+
+        if let Some(color) = self.color {
+            material.base_color = color.into();
+        }
+
+        if let Some(emissive) = self.emissive {
+            material.emissive = Color::from(emissive).into();
+        }
+
+        if let Some(roughness) = self.roughness {
+            material.perceptual_roughness = roughness;
+        }
+
+        if let Some(metallic) = self.metallic {
+            material.metallic = metallic;
+        }
+
+        material
+    }
+}
+
+impl ThingKind {
+    pub fn validate(&self, name: &str) -> Result<(), String> {
+        if self.model.is_empty() {
+            return Err(format!("{name}.model is missing"));
+        }
+
+        for (field, [min, max]) in [
+            ("size", self.size),
+            ("speed", self.speed),
+            ("rotation_speed", self.rotation_speed),
+        ] {
+            if !(min.is_finite() && max.is_finite() && 0.0 <= min && min <= max) {
+                return Err(format!(
+                    "{name}.{field} must be two numbers from 0 up, smallest first, got [{min}, {max}]"
+                ));
+            }
+        }
+
+        for (field, value) in [("size", self.size[0]), ("radius", self.radius)] {
+            if !value.is_finite() || value <= 0.0 {
+                return Err(format!("{name}.{field} must be greater than 0"));
+            }
+        }
+
+        for (field, value) in [("spread", self.spread), ("weight", self.weight)] {
+            if !value.is_finite() || value < 0.0 {
+                return Err(format!("{name}.{field} must be 0 or greater, got {value}"));
+            }
+        }
+
+        if self.one_in == Some(0) {
+            return Err(format!("{name}.one_in must be at least 1"));
+        }
+
+        if let Trajectory::Arc(turn) = self.trajectory
+            && !turn.is_finite()
+        {
+            return Err(format!("{name}.trajectory turns by {turn} radians"));
+        }
+
+        Ok(())
+    }
+}
+
+/// `[min, max]` from the catalog as a range to sample from.
+pub fn range([min, max]: [f32; 2]) -> RangeInclusive<f32> {
+    min..=max
+}
+
+impl Catalog {
+    /// parses and validates a catalog file's text.
+    pub fn from_toml(text: &str) -> Result<Self, CatalogError> {
+        let catalog: Self = toml::from_str(text)?;
+        catalog.validate().map_err(CatalogError::Invalid)?;
+
+        Ok(catalog)
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        for (name, kind) in &self.kinds {
+            kind.validate(name)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// loads `.catalog.toml` files as a `Catalog`.
+#[derive(Default)]
+pub struct CatalogLoader;
+
+impl AssetLoader for CatalogLoader {
+    type Asset = Catalog;
+    type Settings = ();
+    type Error = CatalogError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Catalog, CatalogError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+
+        let text = str::from_utf8(&bytes).map_err(|e| CatalogError::Invalid(e.to_string()))?;
+
+        Catalog::from_toml(text)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["catalog.toml"]
+    }
+}
+
+/// why a catalog couldn't be loaded.
+#[derive(Debug)]
+pub enum CatalogError {
+    Io(io::Error),
+    Parse(toml::de::Error),
+    Invalid(String),
+}
+
+impl fmt::Display for CatalogError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(source) => write!(f, "failed to read the catalog: {source}"),
+            Self::Parse(source) => write!(f, "invalid catalog: {source}"),
+            Self::Invalid(message) => write!(f, "invalid catalog: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for CatalogError {}
+
+impl From<io::Error> for CatalogError {
+    fn from(source: io::Error) -> Self {
+        Self::Io(source)
+    }
+}
+
+impl From<toml::de::Error> for CatalogError {
+    fn from(source: toml::de::Error) -> Self {
+        Self::Parse(source)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::space_stuff::space_objects::cataloged::Cataloged;
+    use rand::{SeedableRng, rngs::StdRng};
+
+    const CATALOG: &str = r##"
+        [comet]
+        model = "models/comet/comet.glb#Scene0"
+        size = [0.2, 0.4]
+        trajectory = { arc = 1.5 }
+        weight = 0.25
+        max_alive = 2
+
+        [probe]
+        model = "models/probe.gltf#Mesh0/Primitive0"
+        material = { texture = "models/probe.png", emissive = "#f5c2e7", metallic = 1.0 }
+        trajectory = "straight"
+        one_in = 50
+    "##;
+
+    fn invalid(text: &str) -> String {
+        match Catalog::from_toml(text) {
+            Err(CatalogError::Invalid(message)) => message,
+            other => panic!("expected the catalog to be invalid, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn kinds_are_read_with_defaults_for_the_rest() {
+        let catalog = Catalog::from_toml(CATALOG).unwrap();
+        let comet = &catalog.kinds["comet"];
+        let probe = &catalog.kinds["probe"];
+
+        assert_eq!(comet.trajectory, Trajectory::Arc(1.5));
+        assert_eq!(comet.size, [0.2, 0.4]);
+        assert_eq!((comet.weight, comet.max_alive), (0.25, Some(2)));
+        assert_eq!(comet.speed, ThingKind::default().speed);
+        assert_eq!(comet.material, None);
+        assert_eq!(probe.trajectory, Trajectory::Straight);
+        assert_eq!(probe.one_in, Some(50));
+        assert_eq!(
+            probe.material,
+            Some(MaterialOverride {
+                texture: Some("models/probe.png".into()),
+                emissive: Some(HexColor(Srgba::hex("#f5c2e7").unwrap())),
+                metallic: Some(1.0),
+                ..default()
+            })
+        );
+    }
+
+    #[test]
+    fn the_shipped_catalog_is_valid() {
+        let catalog =
+            Catalog::from_toml(include_str!("../../../assets/space_stuff.catalog.toml")).unwrap();
+
+        assert!(catalog.kinds.contains_key("asteroid"));
+    }
+
+    #[test]
+    fn kinds_that_cant_spawn_are_rejected() {
+        assert_eq!(
+            invalid("[comet]\nmodel = \"comet.glb#Scene0\"\nsize = [0.3, 0.1]"),
+            "comet.size must be two numbers from 0 up, smallest first, got [0.3, 0.1]"
+        );
+        assert_eq!(invalid("[comet]\nmodel = \"\""), "comet.model is missing");
+        assert_eq!(
+            invalid("[comet]\nsize = [0.1, 0.2]"),
+            "comet.model is missing"
+        );
+        assert_eq!(
+            invalid("[comet]\nmodel = \"comet.glb#Scene0\"\nweight = -1"),
+            "comet.weight must be 0 or greater, got -1"
+        );
+        assert!(matches!(
+            Catalog::from_toml("[comet]\nmodel = \"comet.glb#Scene0\"\nsise = [0.1, 0.2]"),
+            Err(CatalogError::Parse(_))
+        ));
+    }
+
+    #[test]
+    fn valid_kinds_spawn() {
+        let catalog = Catalog::from_toml(
+            "[dot]\nmodel = \"dot.glb#Scene0\"\nsize = [0.2, 0.2]\nspeed = [0, 0]",
+        )
+        .unwrap();
+        let mut rng = StdRng::seed_from_u64(0);
+
+        for (name, kind) in &catalog.kinds {
+            Cataloged::new(name, kind, &mut rng);
+        }
+    }
+}
@@ -0,0 +1,164 @@
+use super::{
+    SpaceThing, SpaceThingTrait,
+    catalog::{ThingKind, Trajectory, range},
+};
+use crate::{Mode, Shape, outputs::in_mode};
+use bevy::{asset::AssetPath, pbr::wireframe::NoWireframe, prelude::*, scene::SceneInstanceReady};
+use rand::Rng;
+use std::f32::consts::PI;
+
+/// a space thing whose looks and motion come from an entry in the `Catalog`.
+#[derive(Clone, Debug, Default, Component)]
+pub struct Cataloged {
+    /// the catalog entry's name.
+    name: String,
+    kind: ThingKind,
+    /// the diameter of the thing in meters
+    size: f32,
+    /// end location
+    going_to: Vec3,
+    /// where to Spawn the thing.
+    spawn_at: Vec3,
+    /// speed in km/s
+    speed: f32,
+    /// the axis on which the object will rotate.
+    rotation_axis: Vec2,
+    rotation_speed: f32,
+    travelled: f32,
+    scale: f32,
+}
+
+impl Cataloged {
+    /// a randomly sized, aimed and spinning thing of the catalog's kind `name`.
+    pub fn new(name: &str, kind: &ThingKind, rng: &mut impl Rng) -> Self {
+        let size = rng.random_range(range(kind.size));
+        let speed = rng.random_range(range(kind.speed));
+        let theta = rng.random_range(0.0..2.0 * PI);
+        let spawn_at = Vec3::ZERO;
+        let going_to = (theta, PI / 2.0, size).into();
+        let rotation_axis = {
+            let theta_x = rng.random_range(0.0..(2.0 * PI));
+            let theta_y = rng.random_range(0.0..(2.0 * PI));
+            (theta_x, theta_y).into()
+        };
+        let rotation_speed = rng.random_range(range(kind.rotation_speed));
+
+        Self {
+            name: name.into(),
+            kind: kind.clone(),
+            size,
+            speed,
+            spawn_at,
+            going_to,
+            rotation_axis,
+            rotation_speed,
+            travelled: 0.0,
+            scale: 0.0,
+        }
+    }
+
+    /// the name of its catalog entry.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl SpaceThingTrait for Cataloged {
+    fn update_orientation(&mut self, time_delta: &Res<Time>, orientation: &mut Transform) {
+        orientation.rotate_x(self.rotation_axis[0] * time_delta.delta_secs() * self.rotation_speed);
+        orientation.rotate_y(self.rotation_axis[1] * time_delta.delta_secs() * self.rotation_speed);
+    }
+
+    fn update_location(&mut self, time_delta: &Res<Time>, location: &mut Transform) {
+        let distance = self.speed * time_delta.delta_secs();
+        self.travelled += distance;
+        let along = self.spawn_at.lerp(self.going_to, self.travelled);
+
+        location.translation = match self.kind.trajectory {
+            Trajectory::Straight => along,
+            Trajectory::Arc(turn) => Quat::from_rotation_z(turn * self.travelled) * along,
+        };
+    }
+
+    fn spawn_model(
+        &mut self,
+        mut cmds: Commands,
+        asset_server: &Res<AssetServer>,
+        materials: &mut ResMut<Assets<StandardMaterial>>,
+        fov: f32,
+    ) {
+        let transform = self.get_transform(fov);
+        let material = self
+            .kind
+            .material
+            .as_ref()
+            .map(|material| materials.add(material.material(asset_server)));
+        let model = AssetPath::parse(&self.kind.model).into_owned();
+        let mut thing = cmds.spawn((
+            SpaceThing::Cataloged(self.clone()),
+            transform,
+            NoWireframe,
+            Shape,
+            in_mode(Mode::SpaceStuff),
+            Visibility::Visible,
+        ));
+
+        match model.label() {
+            Some(label) if !label.starts_with("Scene") => {
+                thing.insert((
+                    Mesh3d(asset_server.load(model)),
+                    MeshMaterial3d(material.unwrap_or_default()),
+                ));
+            }
+            label => {
+                let model = match label {
+                    Some(_) => model,
+                    None => model.with_label(GltfAssetLabel::Scene(0).to_string()),
+                };
+
+                thing.insert(SceneRoot(asset_server.load(model)));
+
+                // a scene's meshes only exist once it's spawned, their materials are swapped then.
+                if let Some(material) = material {
+                    thing.observe(
+                        move |trigger: Trigger<SceneInstanceReady>,
+                              children: Query<&Children>,
+                              mut meshes: Query<&mut MeshMaterial3d<StandardMaterial>>| {
+                            for entity in children.iter_descendants(trigger.entity()) {
+                                if let Ok(mut mesh) = meshes.get_mut(entity) {
+                                    mesh.0 = material.clone();
+                                }
+                            }
+                        },
+                    );
+                }
+            }
+        }
+    }
+
+    fn radius(&self) -> f32 {
+        self.kind.radius * self.scale
+    }
+
+    fn get_transform(&mut self, fov: f32) -> Transform {
+        self.spawn_at[0] = 0.0;
+        self.spawn_at[1] = 0.0;
+        self.spawn_at[2] = -fov;
+
+        self.speed = (self.spawn_at.distance(self.going_to) * self.speed) / fov;
+
+        let scale = self.size * (fov * 0.05);
+        self.scale = scale;
+        self.going_to[2] *= fov * self.kind.spread + scale;
+
+        {
+            let tmp_loc = self.going_to;
+            self.going_to[0] = tmp_loc[2] * tmp_loc[1].sin() * tmp_loc[0].cos();
+            self.going_to[1] = tmp_loc[2] * tmp_loc[1].sin() * tmp_loc[0].sin();
+            self.going_to[2] = tmp_loc[2] * tmp_loc[1].cos();
+        }
+
+        Transform::from_xyz(self.spawn_at[0], self.spawn_at[1], self.spawn_at[2])
+            .with_scale(Vec3::new(scale, scale, scale))
+    }
+}
@@ -1,11 +1,13 @@
-use asteroid::Asteroid;
-use astronaut::Astronaut;
+use super::sighting::Sighting;
 use bevy::prelude::*;
+use cataloged::Cataloged;
 use enum_dispatch::enum_dispatch;
+use satellite::Satellite;
 // use std::path::PathBuf;
 
-pub mod asteroid;
-pub mod astronaut;
+pub mod catalog;
+pub mod cataloged;
+pub mod satellite;
 
 #[enum_dispatch(SpaceThing)]
 pub trait SpaceThingTrait: Default {
@@ -26,24 +28,37 @@ pub trait SpaceThingTrait: Default {
     );
     /// set FOV in km;
     fn get_transform(&mut self, fov: f32) -> Transform;
+    /// how far the SpaceThing reaches from its origin, scale included.
+    fn radius(&self) -> f32;
     /// will return true when the SpaceThing is out of view and should despawn.
-    fn should_despawn(&self) -> bool {
-        false
+    fn should_despawn(&self, sighting: &Sighting) -> bool {
+        sighting.left_view() || sighting.lost()
     }
 }
 
 #[enum_dispatch]
 #[derive(Clone, Debug, Component)]
+#[require(Sighting)]
 pub enum SpaceThing {
-    Asteroid(Asteroid),
-    Astronaut(Astronaut),
+    /// anything from the catalog, the asteroid and astronaut among them.
+    Cataloged(Cataloged),
+    Satellite(Satellite),
     // Alian,
     // AlianShip,
-    // SatiLite,
+}
+
+impl SpaceThing {
+    /// what kind of thing it is, used when reporting status.
+    pub fn kind(&self) -> &str {
+        match self {
+            Self::Cataloged(thing) => thing.name(),
+            Self::Satellite(_) => Satellite::NAME,
+        }
+    }
 }
 
 impl Default for SpaceThing {
     fn default() -> Self {
-        Self::Asteroid(Asteroid::default())
+        Self::Cataloged(Cataloged::default())
     }
 }
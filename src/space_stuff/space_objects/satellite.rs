@@ -0,0 +1,300 @@
+use super::{SpaceThing, SpaceThingTrait};
+use crate::{
+    Mode, Shape,
+    outputs::{ModeEntity, in_mode},
+    space_stuff::{
+        CAMERA_Z,
+        sighting::{Sighting, ViewSize},
+    },
+};
+use bevy::{pbr::wireframe::NoWireframe, prelude::*};
+use rand::Rng;
+use std::f32::consts::{FRAC_PI_2, PI, TAU};
+
+/// how finely the ends of a satellite's arc are looked for, in radians.
+const SWEEP_STEP: f32 = 0.01;
+/// radians per second the solar panels turn to follow the light.
+const TRACK_SPEED: f32 = 0.2;
+/// half the bus's width, where the panel arms attach.
+const BUS_HALF_WIDTH: f32 = 0.3;
+
+/// a satellite built from boxes, crossing the view on a curved orbit with its panels turned to
+/// the light.
+#[derive(Clone, Debug, Component)]
+pub struct Satellite {
+    /// how big it is, in meters.
+    size: f32,
+    /// the middle of its orbit.
+    center: Vec3,
+    /// turns the orbit's plane from facing the camera.
+    plane: Quat,
+    radius: f32,
+    /// where on the orbit it starts and ends, in radians.
+    from: f32,
+    to: f32,
+    /// seconds it takes to cross.
+    duration: f32,
+    /// how far across it's got, from 0 to 1.
+    travelled: f32,
+}
+
+/// one of the satellite's solar panel wings, turning around its arm to face the light.
+#[derive(Clone, Copy, Debug, Default, Component)]
+pub struct SolarPanel {
+    angle: f32,
+}
+
+impl Default for Satellite {
+    fn default() -> Self {
+        Self::new(ViewSize::default(), &mut rand::rng())
+    }
+}
+
+impl Satellite {
+    /// its name in the spawn table and status.
+    pub const NAME: &str = "satellite";
+
+    /// a satellite on a random arc across a view of `view`'s size.
+    pub fn new(view: ViewSize, rng: &mut impl Rng) -> Self {
+        let size = rng.random_range(1.5..3.0);
+        let depth = rng.random_range(40.0..90.0);
+        // the orbit is wider than the view, so the arc across it is gentle.
+        let half_width = depth * view.half_width + Self::reach(size);
+        let radius = half_width * rng.random_range(1.2..3.0);
+        // keeps it within the middle of the view's height.
+        let height = depth * view.half_height * rng.random_range(-0.6..0.6);
+        let plane = Quat::from_rotation_z(rng.random_range(-0.5..0.5))
+            * Quat::from_rotation_x(rng.random_range(-0.3..0.3));
+        let top = Vec3::new(0.0, height, CAMERA_Z - depth);
+        let mut satellite = Self {
+            size,
+            center: top - plane * Vec3::new(0.0, radius, 0.0),
+            plane,
+            radius,
+            from: FRAC_PI_2,
+            to: FRAC_PI_2,
+            duration: rng.random_range(25.0..45.0),
+            travelled: 0.0,
+        };
+        // the top of the orbit passes through the view, it starts and ends just out of it.
+        let (left, right) = (satellite.sweep(view, 1.0), satellite.sweep(view, -1.0));
+
+        (satellite.from, satellite.to) = if rng.random_bool(0.5) {
+            (FRAC_PI_2 + left, FRAC_PI_2 - right)
+        } else {
+            (FRAC_PI_2 - right, FRAC_PI_2 + left)
+        };
+
+        satellite
+    }
+
+    /// how far around from the top of its orbit it goes before it's out of `view`, going
+    /// anticlockwise for a `turn` of 1 and clockwise for -1.
+    fn sweep(&self, view: ViewSize, turn: f32) -> f32 {
+        let camera = Vec3::Z * CAMERA_Z;
+        let mut sweep = 0.0;
+
+        while sweep < PI && view.contains(self.at(FRAC_PI_2 + turn * sweep) - camera, self.radius())
+        {
+            sweep += SWEEP_STEP;
+        }
+
+        sweep
+    }
+
+    /// how far a satellite of `size` reaches from its middle. the panels reach about 1.7 out.
+    fn reach(size: f32) -> f32 {
+        1.8 * size
+    }
+
+    /// how far around its orbit it is.
+    fn angle(&self) -> f32 {
+        self.from + (self.to - self.from) * self.travelled
+    }
+
+    /// where on its orbit it is at `angle`.
+    fn at(&self, angle: f32) -> Vec3 {
+        let (sin, cos) = angle.sin_cos();
+
+        self.center + self.plane * Vec3::new(cos, sin, 0.0) * self.radius
+    }
+
+    fn position(&self) -> Vec3 {
+        self.at(self.angle())
+    }
+
+    /// the panel arms point away from the middle of the orbit, like a satellite keeping one face
+    /// to the planet it goes around.
+    fn rotation(&self) -> Quat {
+        self.plane * Quat::from_rotation_z(self.angle())
+    }
+}
+
+impl SpaceThingTrait for Satellite {
+    fn update_orientation(&mut self, _time_delta: &Res<Time>, orientation: &mut Transform) {
+        orientation.rotation = self.rotation();
+    }
+
+    fn update_location(&mut self, time_delta: &Res<Time>, location: &mut Transform) {
+        self.travelled += time_delta.delta_secs() / self.duration;
+        location.translation = self.position();
+    }
+
+    fn spawn_model(
+        &mut self,
+        mut cmds: Commands,
+        asset_server: &Res<AssetServer>,
+        materials: &mut ResMut<Assets<StandardMaterial>>,
+        fov: f32,
+    ) {
+        let bus = asset_server.add(Mesh::from(Cuboid::new(BUS_HALF_WIDTH * 2.0, 0.6, 0.9)));
+        let arm = asset_server.add(Mesh::from(Cuboid::new(0.5, 0.05, 0.05)));
+        let panel = asset_server.add(Mesh::from(Cuboid::new(1.2, 0.02, 0.6)));
+        let foil = materials.add(StandardMaterial {
+            base_color: Color::srgb_u8(0xd4, 0xa0, 0x17),
+            metallic: 0.8,
+            perceptual_roughness: 0.35,
+            ..default()
+        });
+        let steel = materials.add(StandardMaterial {
+            base_color: Color::srgb_u8(0x93, 0x99, 0xb2),
+            metallic: 0.6,
+            perceptual_roughness: 0.5,
+            ..default()
+        });
+        let cells = materials.add(StandardMaterial {
+            base_color: Color::srgb_u8(0x1b, 0x2a, 0x6b),
+            metallic: 0.3,
+            perceptual_roughness: 0.2,
+            ..default()
+        });
+        let transform = self.get_transform(fov);
+
+        cmds.spawn((
+            SpaceThing::Satellite(self.clone()),
+            transform,
+            NoWireframe,
+            Shape,
+            in_mode(Mode::SpaceStuff),
+            Visibility::Visible,
+        ))
+        .with_children(|satellite| {
+            satellite.spawn((Mesh3d(bus), MeshMaterial3d(foil), NoWireframe));
+
+            for side in [-1.0, 1.0] {
+                satellite
+                    .spawn((
+                        SolarPanel::default(),
+                        Transform::from_xyz(side * BUS_HALF_WIDTH, 0.0, 0.0),
+                        Visibility::Inherited,
+                    ))
+                    .with_children(|wing| {
+                        wing.spawn((
+                            Mesh3d(arm.clone()),
+                            MeshMaterial3d(steel.clone()),
+                            Transform::from_xyz(side * 0.25, 0.0, 0.0),
+                            NoWireframe,
+                        ));
+                        wing.spawn((
+                            Mesh3d(panel.clone()),
+                            MeshMaterial3d(cells.clone()),
+                            Transform::from_xyz(side * 1.1, 0.0, 0.0),
+                            NoWireframe,
+                        ));
+                    });
+            }
+        });
+    }
+
+    /// satellites fly close by, `fov` is only for things coming from the far distance.
+    fn get_transform(&mut self, _fov: f32) -> Transform {
+        Transform::from_translation(self.position())
+            .with_rotation(self.rotation())
+            .with_scale(Vec3::splat(self.size))
+    }
+
+    fn radius(&self) -> f32 {
+        Self::reach(self.size)
+    }
+
+    fn should_despawn(&self, sighting: &Sighting) -> bool {
+        sighting.left_view() || sighting.lost() || self.travelled >= 1.0
+    }
+}
+
+/// turns solar panels around their arms towards the nearest space stuff light, a little at a
+/// time.
+pub fn track_light(
+    time: Res<Time>,
+    lights: Query<(&GlobalTransform, &ModeEntity), With<PointLight>>,
+    satellites: Query<&GlobalTransform, With<SpaceThing>>,
+    mut panels: Query<(&mut SolarPanel, &mut Transform, &Parent)>,
+) {
+    let step = TRACK_SPEED * time.delta_secs();
+
+    for (mut panel, mut transform, parent) in &mut panels {
+        let Ok(satellite) = satellites.get(parent.get()) else {
+            continue;
+        };
+        let Some(light) = lights
+            .iter()
+            .filter(|(_, mode)| mode.0 == Mode::SpaceStuff)
+            .map(|(light, _)| light.translation())
+            .min_by(|a, b| {
+                let to = |light: &Vec3| light.distance_squared(satellite.translation());
+                to(a).total_cmp(&to(b))
+            })
+        else {
+            continue;
+        };
+
+        // the light as seen from the panel's arm, which runs along x.
+        let towards = satellite.affine().inverse().transform_point3(light) - transform.translation;
+        // the panel's face points along y when flat, turning by `angle` around x.
+        let target = towards.z.atan2(towards.y);
+        let turn = (target - panel.angle + PI).rem_euclid(TAU) - PI;
+
+        panel.angle += turn.clamp(-step, step);
+        transform.rotation = Quat::from_rotation_x(panel.angle);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::{
+        math::Vec3A,
+        render::{camera::CameraProjection, primitives::Sphere},
+    };
+    use rand::{SeedableRng, rngs::StdRng};
+
+    #[test]
+    fn satellites_cross_the_whole_view() {
+        // three 1920x1080 monitors spanned into one 5760x1080 view.
+        let projection = PerspectiveProjection {
+            aspect_ratio: 5760.0 / 1080.0,
+            ..default()
+        };
+        let view = ViewSize::of(&projection, None);
+        let camera = GlobalTransform::from_xyz(0.0, 0.0, CAMERA_Z);
+        let frustum = projection.compute_frustum(&camera);
+        let mut rng = StdRng::seed_from_u64(4);
+
+        for _ in 0..200 {
+            let mut satellite = Satellite::new(view, &mut rng);
+            let mut seen_at = |travelled| {
+                satellite.travelled = travelled;
+                let sphere = Sphere {
+                    center: Vec3A::from(satellite.position()),
+                    radius: satellite.radius(),
+                };
+
+                frustum.intersects_sphere(&sphere, true)
+            };
+
+            assert!(!seen_at(0.0));
+            assert!(seen_at(0.5));
+            assert!(!seen_at(1.0));
+        }
+    }
+}
@@ -0,0 +1,334 @@
+use super::space_objects::catalog::{Catalog, ThingKind};
+use crate::config::{SpaceStuffConfig, SpawnOverride, SpawnSchedule};
+use bevy::prelude::*;
+use rand::{Rng, distr::weighted::Error as WeightError, seq::IndexedRandom};
+use std::collections::{BTreeMap, HashMap};
+
+/// how one kind of space thing gets picked to spawn.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SpawnRule {
+    pub weight: f32,
+    pub max_alive: Option<u32>,
+    /// rolled for before anything is picked by weight, see `ThingKind::one_in`.
+    pub one_in: Option<u32>,
+}
+
+impl From<&ThingKind> for SpawnRule {
+    fn from(kind: &ThingKind) -> Self {
+        Self {
+            weight: kind.weight,
+            max_alive: kind.max_alive,
+            one_in: kind.one_in,
+        }
+    }
+}
+
+impl SpawnRule {
+    /// this rule with the config's changes to it.
+    pub fn with(self, change: &SpawnOverride) -> Self {
+        Self {
+            weight: change.weight.unwrap_or(self.weight),
+            max_alive: change.max_alive.or(self.max_alive),
+            one_in: change.one_in.or(self.one_in),
+        }
+    }
+}
+
+/// every kind of space thing that can spawn, and how likely each is to.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SpawnTable {
+    rules: Vec<(String, SpawnRule)>,
+}
+
+impl SpawnTable {
+    /// the kinds built into the code and the catalog's, with the config's changes on top. the
+    /// catalog's replace built in kinds of the same name.
+    pub fn new(
+        built_in: &[(&str, SpawnRule)],
+        catalog: &Catalog,
+        changes: &BTreeMap<String, SpawnOverride>,
+    ) -> Self {
+        let mut table = Self::default();
+        let catalog = catalog
+            .kinds
+            .iter()
+            .map(|(name, kind)| (name.as_str(), SpawnRule::from(kind)));
+
+        for (name, rule) in built_in.iter().copied().chain(catalog) {
+            table.insert(
+                name,
+                changes.get(name).map_or(rule, |change| rule.with(change)),
+            );
+        }
+
+        table
+    }
+
+    /// adds the kind `name`, replacing any already in the table.
+    fn insert(&mut self, name: &str, rule: SpawnRule) {
+        self.rules.retain(|(other, _)| other != name);
+        self.rules.push((name.into(), rule));
+    }
+
+    /// the kind to spawn next, given how many of each are alive, or why there's nothing to.
+    pub fn pick(&self, alive: &HashMap<&str, usize>, rng: &mut impl Rng) -> Result<&str, String> {
+        let open: Vec<_> = self
+            .rules
+            .iter()
+            .filter(|(name, rule)| {
+                rule.max_alive
+                    .is_none_or(|max| alive.get(name.as_str()).copied().unwrap_or(0) < max as usize)
+            })
+            .collect();
+
+        // the rare ones get their chance first, in the order they were added.
+        for (name, rule) in &open {
+            if let Some(one_in) = rule.one_in
+                && rng.random_ratio(1, one_in)
+            {
+                return Ok(name);
+            }
+        }
+
+        if open.is_empty() {
+            return Err("every kind is at its max_alive".into());
+        }
+
+        let common: Vec<_> = open
+            .into_iter()
+            .filter(|(_, rule)| rule.one_in.is_none())
+            .collect();
+
+        match common.choose_weighted(rng, |(_, rule)| rule.weight) {
+            Ok((name, _)) => Ok(name),
+            Err(WeightError::InvalidInput) => {
+                Err("only rare kinds are below their max_alive and none came up".into())
+            }
+            Err(WeightError::InsufficientNonZero) => {
+                Err("every kind below its max_alive has a weight of 0".into())
+            }
+            Err(e) => Err(format!("can't pick by weight: {e}")),
+        }
+    }
+}
+
+/// keeps track of when the next space things are due. time is counted up rather than checked
+/// against the clock, so spawns keep to the schedule whatever the frame rate.
+#[derive(Resource, Clone, Debug, Default, PartialEq)]
+pub struct SpawnScheduler {
+    /// seconds until the next spawn, or burst.
+    until_next: f32,
+    /// spawns that came due and haven't happened yet.
+    due: u32,
+}
+
+impl SpawnScheduler {
+    /// moves the schedule on by `delta` seconds with `alive` things on screen.
+    pub fn tick(
+        &mut self,
+        config: &SpaceStuffConfig,
+        delta: f32,
+        alive: usize,
+        rng: &mut impl Rng,
+    ) {
+        let pace = match config.target_alive {
+            Some(target) if alive >= target as usize => return,
+            Some(target) if alive * 2 < target as usize => 2.0,
+            _ => 1.0,
+        };
+
+        self.until_next -= delta * pace;
+
+        while self.until_next <= 0.0 {
+            let interval = config.spawn_interval;
+
+            (self.until_next, self.due) = match config.schedule {
+                SpawnSchedule::Interval => (self.until_next + interval, self.due + 1),
+                // exponentially distributed gaps. `1.0 - random` is never 0, so ln stays finite.
+                SpawnSchedule::Poisson => (
+                    self.until_next - interval * (1.0 - rng.random::<f32>()).ln(),
+                    self.due + 1,
+                ),
+                SpawnSchedule::Burst => (
+                    self.until_next + interval * config.burst_size as f32,
+                    self.due + config.burst_size,
+                ),
+            };
+        }
+    }
+
+    /// the spawns that are due, which are then no longer due.
+    pub fn take(&mut self) -> u32 {
+        std::mem::take(&mut self.due)
+    }
+
+    /// makes something due right away, as when the mode starts.
+    pub fn restart(&mut self) {
+        *self = Self::default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{SeedableRng, rngs::StdRng};
+
+    const PICKS: usize = 20_000;
+
+    fn rule(weight: f32, max_alive: Option<u32>, one_in: Option<u32>) -> SpawnRule {
+        SpawnRule {
+            weight,
+            max_alive,
+            one_in,
+        }
+    }
+
+    fn table(rules: &[(&str, SpawnRule)]) -> SpawnTable {
+        let mut table = SpawnTable::default();
+
+        for (name, rule) in rules {
+            table.insert(name, *rule);
+        }
+
+        table
+    }
+
+    /// how many times each kind comes up in `PICKS` picks.
+    fn picks(table: &SpawnTable, alive: &HashMap<&str, usize>) -> HashMap<String, usize> {
+        let mut rng = StdRng::seed_from_u64(3);
+        let mut picked = HashMap::new();
+
+        for _ in 0..PICKS {
+            *picked
+                .entry(table.pick(alive, &mut rng).unwrap().to_owned())
+                .or_default() += 1;
+        }
+
+        picked
+    }
+
+    #[test]
+    fn kinds_come_up_by_weight() {
+        let table = table(&[
+            ("asteroid", rule(1.0, None, None)),
+            ("astronaut", rule(3.0, None, None)),
+        ]);
+        let picked = picks(&table, &HashMap::new());
+        let ratio = picked["astronaut"] as f32 / picked["asteroid"] as f32;
+
+        assert!((2.8..3.2).contains(&ratio), "{ratio}");
+    }
+
+    #[test]
+    fn kinds_at_their_limit_are_left_out() {
+        let table = table(&[
+            ("asteroid", rule(1.0, Some(2), None)),
+            ("astronaut", rule(1.0, Some(2), None)),
+        ]);
+        let alive = HashMap::from([("asteroid", 2), ("astronaut", 1)]);
+
+        assert_eq!(picks(&table, &alive)["astronaut"], PICKS);
+
+        let alive = HashMap::from([("asteroid", 2), ("astronaut", 2)]);
+        let why = table.pick(&alive, &mut StdRng::seed_from_u64(3));
+
+        assert_eq!(why, Err("every kind is at its max_alive".into()));
+    }
+
+    #[test]
+    fn rare_kinds_come_up_one_in_n() {
+        let table = table(&[
+            ("asteroid", rule(1.0, None, None)),
+            ("comet", rule(1.0, None, Some(50))),
+        ]);
+        let rate = picks(&table, &HashMap::new())["comet"] as f32 / PICKS as f32;
+
+        assert!((0.016..0.024).contains(&rate), "{rate}");
+    }
+
+    #[test]
+    fn without_weights_nothing_is_picked() {
+        let table = table(&[("asteroid", rule(0.0, None, None))]);
+        let why = table.pick(&HashMap::new(), &mut StdRng::seed_from_u64(3));
+
+        assert_eq!(
+            why,
+            Err("every kind below its max_alive has a weight of 0".into())
+        );
+    }
+
+    /// how many spawns come due over `secs` seconds of frames `delta` apart, with `alive` things
+    /// on screen.
+    fn spawned(config: &SpaceStuffConfig, delta: f32, secs: f32, alive: usize) -> u32 {
+        let mut scheduler = SpawnScheduler::default();
+        let mut rng = StdRng::seed_from_u64(5);
+        let mut spawned = 0;
+
+        for _ in 0..(secs / delta).round() as u32 {
+            scheduler.tick(config, delta, alive, &mut rng);
+            spawned += scheduler.take();
+        }
+
+        spawned
+    }
+
+    fn schedule(schedule: SpawnSchedule) -> SpaceStuffConfig {
+        SpaceStuffConfig {
+            schedule,
+            ..default()
+        }
+    }
+
+    #[test]
+    fn spawns_keep_to_the_schedule_whatever_the_frame_rate() {
+        // one right away then one every interval, or a burst right away then one every burst's
+        // worth of intervals.
+        for (schedule, expected) in [(SpawnSchedule::Interval, 25), (SpawnSchedule::Burst, 27)] {
+            let config = self::schedule(schedule);
+            let slow = spawned(&config, 1.0 / 10.0, 61.0, 0);
+            let fast = spawned(&config, 1.0 / 240.0, 61.0, 0);
+
+            assert!(slow.abs_diff(fast) <= 1, "{schedule:?}: {slow} and {fast}");
+            assert_eq!(slow, expected, "{schedule:?}");
+        }
+    }
+
+    #[test]
+    fn poisson_gaps_average_the_interval() {
+        let config = schedule(SpawnSchedule::Poisson);
+        let secs = 5000.0;
+
+        for delta in [1.0 / 10.0, 1.0 / 240.0] {
+            let gap = secs / spawned(&config, delta, secs, 0) as f32;
+
+            assert!(
+                (gap - config.spawn_interval).abs() < config.spawn_interval * 0.1,
+                "{gap}"
+            );
+        }
+    }
+
+    #[test]
+    fn spawning_holds_off_around_the_target() {
+        let config = SpaceStuffConfig {
+            target_alive: Some(8),
+            ..schedule(SpawnSchedule::Interval)
+        };
+        let normal = spawned(&config, 1.0 / 60.0, 60.0, 5);
+
+        assert_eq!(spawned(&config, 1.0 / 60.0, 60.0, 8), 0);
+        assert_eq!(spawned(&config, 1.0 / 60.0, 60.0, 12), 0);
+        // well under the target, it spawns twice as fast to catch up.
+        assert!(spawned(&config, 1.0 / 60.0, 60.0, 3).abs_diff(normal * 2) <= 1);
+    }
+
+    #[test]
+    fn later_kinds_replace_earlier_ones() {
+        let table = table(&[
+            ("satellite", rule(0.5, Some(2), None)),
+            ("satellite", rule(1.0, None, None)),
+        ]);
+
+        assert_eq!(table.rules, [("satellite".into(), rule(1.0, None, None))]);
+    }
+}
@@ -0,0 +1,150 @@
+use rand::{SeedableRng, rngs::StdRng, seq::SliceRandom};
+
+/// perlin noise that also gives its gradient, worked out from the same interpolation as the
+/// value instead of sampling around the point.
+#[derive(Clone, Debug)]
+pub struct Perlin {
+    perm: [u8; 256],
+}
+
+impl Perlin {
+    pub fn new(seed: u32) -> Self {
+        let mut perm = [0; 256];
+
+        for (i, p) in perm.iter_mut().enumerate() {
+            *p = i as u8;
+        }
+
+        perm.shuffle(&mut StdRng::seed_from_u64(seed.into()));
+
+        Self { perm }
+    }
+
+    /// the noise at `point`, roughly in `-1.0..=1.0`, and its gradient there.
+    pub fn sample<const N: usize>(&self, point: [f32; N]) -> (f32, [f32; N]) {
+        let mut cell = [0; N];
+        let mut offset = [0.0; N];
+        let mut fade = [0.0; N];
+        let mut dfade = [0.0; N];
+
+        for d in 0..N {
+            let floor = point[d].floor();
+            cell[d] = floor as i32;
+            offset[d] = point[d] - floor;
+            (fade[d], dfade[d]) = quintic(offset[d]);
+        }
+
+        let mut value = 0.0;
+        let mut gradient = [0.0; N];
+
+        for corner in 0..1usize << N {
+            let g = self.corner_gradient::<N>(cell, corner);
+            let mut dot = 0.0;
+            let mut weight = 1.0;
+            // the weight's derivative along each axis, without the corner's own factor.
+            let mut others = [1.0; N];
+
+            for d in 0..N {
+                let high = corner >> d & 1 == 1;
+                let (w, dw) = if high {
+                    (fade[d], dfade[d])
+                } else {
+                    (1.0 - fade[d], -dfade[d])
+                };
+
+                dot += g[d] * (offset[d] - if high { 1.0 } else { 0.0 });
+
+                for (k, other) in others.iter_mut().enumerate() {
+                    *other *= if k == d { dw } else { w };
+                }
+
+                weight *= w;
+            }
+
+            value += weight * dot;
+
+            for d in 0..N {
+                gradient[d] += weight * g[d] + others[d] * dot;
+            }
+        }
+
+        // the corner gradients are at most √N/2 from the cell center, this brings it back to ±1.
+        let scale = 2.0 / (N as f32).sqrt();
+
+        (value * scale, gradient.map(|g| g * scale))
+    }
+
+    /// one of the vectors pointing from the center of a hypercube to the middle of its edges.
+    fn corner_gradient<const N: usize>(&self, cell: [i32; N], corner: usize) -> [f32; N] {
+        let hash = (0..N).fold(0usize, |hash, d| {
+            let at = cell[d] + (corner >> d & 1) as i32;
+            self.perm[(hash + (at & 255) as usize) & 255] as usize
+        });
+        let zero = hash % N;
+        let mut signs = hash / N;
+        let mut g = [0.0; N];
+
+        for (d, g) in g.iter_mut().enumerate() {
+            if d != zero {
+                *g = if signs & 1 == 1 { -1.0 } else { 1.0 };
+                signs >>= 1;
+            }
+        }
+
+        g
+    }
+}
+
+/// `6t⁵ - 15t⁴ + 10t³` and its derivative.
+fn quintic(t: f32) -> (f32, f32) {
+    (
+        t * t * t * (t * (t * 6.0 - 15.0) + 10.0),
+        30.0 * t * t * (t * (t - 2.0) + 1.0),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+
+    /// the gradient at `point` from central differences of the value.
+    fn differences<const N: usize>(perlin: &Perlin, point: [f32; N]) -> [f32; N] {
+        let step = 1e-3;
+
+        std::array::from_fn(|d| {
+            let (mut above, mut below) = (point, point);
+            above[d] += step;
+            below[d] -= step;
+
+            (perlin.sample(above).0 - perlin.sample(below).0) / (2.0 * step)
+        })
+    }
+
+    #[test]
+    fn gradient_matches_central_differences() {
+        let perlin = Perlin::new(7);
+        let mut rng = StdRng::seed_from_u64(11);
+
+        for _ in 0..500 {
+            let point: [f32; 4] = std::array::from_fn(|_| rng.random_range(-20.0..20.0));
+            let (_, gradient) = perlin.sample(point);
+            let expected = differences(&perlin, point);
+
+            for (got, expected) in gradient.iter().zip(expected) {
+                assert!(
+                    (got - expected).abs() < 2e-2,
+                    "at {point:?}: {gradient:?} against {expected:?}"
+                );
+            }
+        }
+
+        // the 3d noise works the same way.
+        let point = [0.3, -1.7, 5.2];
+        let (_, gradient) = perlin.sample(point);
+
+        for (got, expected) in gradient.iter().zip(differences(&perlin, point)) {
+            assert!((got - expected).abs() < 2e-2);
+        }
+    }
+}
@@ -1,23 +1,87 @@
-use crate::{DebugTexture, Mode};
+use crate::{
+    DebugTexture, Mode, WallpaperRng,
+    config::{Config, SphereConfig, SphereNoise, SphereUpdate},
+    outputs::{ActiveModes, ModeEntity, ModeView, ModeViews, in_mode},
+    palette::Palette,
+    quality::Quality,
+};
 use bevy::{
     pbr::wireframe::{NoWireframe, WireframeColor},
     prelude::*,
+    render::mesh::VertexAttributeValues,
+    tasks::{ComputeTaskPool, TaskPool},
 };
-use noise::{NoiseFn, Perlin};
 use rand::Rng;
 use std::{
     f32::consts::{PI, TAU},
+    mem,
     ops::Deref,
+    time::Duration,
 };
+use surface::Sampler;
+
+pub mod gradient;
+pub mod surface;
+
+/// alpha of the translucent bounding sphere.
+const BOUNDING_ALPHA: f32 = 8. / 255.;
+/// alpha of the bounding sphere's wireframe.
+const BOUNDING_WIREFRAME_ALPHA: f32 = 32. / 255.;
+/// how fast the surface drifts through the noise, in noise units per second.
+const DRIFT: f32 = 0.5;
+/// the spatial direction the loop leans into, so the drift isn't lined up with an axis.
+const DRIFT_AXIS: Vec3 = Vec3::new(0.577_350_3, 0.577_350_3, 0.577_350_3);
+/// the fewest vertices worth handing to a task of their own.
+const MIN_CHUNK: usize = 1024;
+/// seconds it takes to fade from one noise to the next.
+const CROSSFADE_SECS: f32 = 2.0;
+/// turns per second the spheres rotate at.
+const ROTATION_SPEED: f32 = 0.03125;
+/// the bounding sphere's radius, the undulating sphere's is 1.
+const BOUNDING_RADIUS: f32 = 1.25;
+/// how far the camera is from the spheres' center.
+const CAMERA_DISTANCE: f32 = 4.0;
+
+/// the noise the sphere should undulate with. follows the config, and can be changed with
+/// bg-cli.
+#[derive(Resource, Clone, Debug, Default, PartialEq, Deref, DerefMut)]
+pub struct SelectedNoise(pub SphereNoise);
+
+/// the config's noise the last time `SelectedNoise` followed it.
+#[derive(Resource, Default)]
+struct NoiseFromConfig(Option<SphereNoise>);
 
-#[derive(Clone, Copy, Debug, Resource)]
-pub struct PerlinWrapper(Perlin);
+/// the noise the sphere is drawn with right now.
+#[derive(Resource, Clone, Debug)]
+pub struct Undulation {
+    pub seed: u32,
+    pub noise: SphereNoise,
+    /// the surface being faded out after a change, and how far the fade has come from 0 to 1.
+    /// it's still fading itself if the noise changed again before the last fade was done.
+    pub fading: Option<(Box<Undulation>, f32)>,
+}
 
 #[derive(Clone, Copy, Debug, Component)]
 pub struct UndulateSphere;
 
-#[derive(Clone, Copy, Debug, Component)]
-pub struct BaseSphere;
+/// where the undulating sphere's vertices sit before being moved, taken from its mesh once when
+/// it's spawned.
+#[derive(Clone, Debug, Component)]
+pub struct BasePositions(pub Vec<Vec3>);
+
+/// the sphere's surface at the last and the next update, blended between for
+/// `SphereUpdate::Interpolated`.
+#[derive(Clone, Debug, Default, Component)]
+pub struct Keyframes {
+    last: Keyframe,
+    next: Keyframe,
+}
+
+#[derive(Clone, Debug, Default)]
+struct Keyframe {
+    positions: Vec<[f32; 3]>,
+    normals: Vec<[f32; 3]>,
+}
 
 #[derive(Clone, Copy, Debug, Component)]
 pub struct BoundingSphere;
@@ -25,8 +89,9 @@ pub struct BoundingSphere;
 #[derive(Component, Deref, DerefMut)]
 pub struct UndulateTimer(Timer);
 
+/// how far through its loop the sphere's surface is, from 0 up to 1.
 #[derive(Resource, Default, Deref, DerefMut)]
-pub struct Zoom(f32);
+pub struct LoopPhase(pub f32);
 
 // Define a component to designate a rotation speed to an entity.
 #[derive(Component, Clone, Copy, Debug)]
@@ -34,44 +99,111 @@ struct Rotatable {
     speed: f32,
 }
 
+/// exists while at least one output shows the sphere.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct SphereShown;
+
+impl ComputedStates for SphereShown {
+    type SourceStates = ActiveModes;
+
+    fn compute(active: ActiveModes) -> Option<Self> {
+        active.0.contains(&Mode::Sphere).then_some(Self)
+    }
+}
+
 #[derive(Default)]
 pub struct SphereMode;
 
 impl Plugin for SphereMode {
     fn build(&self, app: &mut App) {
-        app.add_systems(
-            OnEnter(Mode::Sphere),
-            (gen_perlin, add_sphere, camera_setup, timer_setup),
-        )
-        .add_systems(OnExit(Mode::Sphere), camera_teardown)
-        .add_systems(Update, (undulate_sphere, rotate_sphere));
+        app.init_resource::<Config>()
+            .init_resource::<Palette>()
+            .init_resource::<Quality>()
+            .init_resource::<WallpaperRng>()
+            .init_resource::<SelectedNoise>()
+            .init_resource::<NoiseFromConfig>()
+            .init_resource::<ModeViews>()
+            .init_state::<ActiveModes>()
+            .add_computed_state::<SphereShown>()
+            .add_systems(
+                OnEnter(SphereShown),
+                (
+                    (select_noise, start_undulation).chain(),
+                    add_sphere,
+                    light_setup,
+                    timer_setup,
+                ),
+            )
+            .add_systems(OnExit(SphereShown), scene_teardown)
+            .add_systems(
+                Update,
+                (
+                    select_noise.run_if(resource_changed::<Config>),
+                    update_view
+                        .after(select_noise)
+                        .run_if(resource_changed::<Config>.or(resource_changed::<SelectedNoise>)),
+                    (
+                        resize_sphere.run_if(resource_changed::<Quality>),
+                        crossfade_noise,
+                        advance_phase,
+                        undulate_sphere,
+                        rotate_sphere,
+                    )
+                        .chain()
+                        .run_if(in_state(SphereShown)),
+                    apply_sphere_colors.run_if(resource_changed::<Palette>),
+                ),
+            );
+
+        app.world_mut().resource_mut::<ModeViews>().insert(
+            Mode::Sphere,
+            camera_view(&SphereConfig::default(), &SphereNoise::default()),
+        );
     }
 }
 
-// fn should_undulate(time: Res<Time>, mut timer: Single<&mut UndulateTimer>) -> bool {
-//     timer.tick(time.delta()).just_finished()
-// }
-
 fn timer_setup(mut commands: Commands) {
     // Add an entity to the world with a timer
-    commands.spawn(UndulateTimer(Timer::from_seconds(
-        1.0 / 2.0,
-        TimerMode::Repeating,
-    )));
+    commands.spawn((
+        UndulateTimer(Timer::from_seconds(1.0 / 2.0, TimerMode::Repeating)),
+        in_mode(Mode::Sphere),
+    ));
 }
 
-fn camera_setup(mut commands: Commands) {
-    commands.spawn((
-        Camera3d::default(),
-        Transform::from_xyz(0.0, 0.0, 4.0).looking_at(Vec3::new(0.0, 0.0, 0.0), Vec3::Y),
-        Camera::default(),
-        Projection::Perspective(PerspectiveProjection {
+/// how the sphere is filmed, every output showing it gets a camera set up like this.
+fn camera_view(settings: &SphereConfig, noise: &SphereNoise) -> ModeView {
+    let mut view = ModeView {
+        transform: Transform::from_xyz(0.0, 0.0, CAMERA_DISTANCE)
+            .looking_at(Vec3::new(0.0, 0.0, 0.0), Vec3::Y),
+        projection: PerspectiveProjection {
             // far: 1_000.0,
             far: 1_000_000.0,
             ..default()
-        }),
-    ));
+        },
+        fps: 0.0,
+    };
+    let nearest = CAMERA_DISTANCE - BOUNDING_RADIUS;
+    // the front of the bounding sphere moves across the view the fastest as it turns.
+    let turning = view.smooth_fps(TAU * ROTATION_SPEED * BOUNDING_RADIUS, nearest);
+    let surface = match settings.update {
+        // the surface only moves this often, the turning keeps going in between.
+        SphereUpdate::Stepped => settings.update_rate,
+        // drifting across a bump raises and lowers the surface by about the noise's amplitude.
+        // finer fractal octaves are fainter, and hardly seen between frames.
+        SphereUpdate::EveryFrame | SphereUpdate::Interpolated => {
+            view.smooth_fps(DRIFT * noise.frequency * noise.amplitude, nearest)
+        }
+    };
+    view.fps = turning.max(surface);
 
+    view
+}
+
+fn update_view(config: Res<Config>, selected: Res<SelectedNoise>, mut views: ResMut<ModeViews>) {
+    views.insert(Mode::Sphere, camera_view(&config.sphere, &selected));
+}
+
+fn light_setup(mut commands: Commands) {
     let intensity = 10_000_000.0;
     let light = PointLight {
         shadows_enabled: true,
@@ -87,25 +219,58 @@ fn camera_setup(mut commands: Commands) {
         light,
         // Transform::from_xyz(8.0, 16.0, 8.0),
         Transform::from_xyz(1.0, 1.0, 8.0).looking_at(Vec3::new(0.0, 0.0, 0.0), Vec3::Y),
+        in_mode(Mode::Sphere),
     ));
 }
 
-fn camera_teardown(
-    mut cmds: Commands,
-    mut cameras: Query<Entity, With<Camera>>,
-    mut lights: Query<Entity, With<PointLight>>,
-) {
-    for camera in cameras.iter() {
-        cmds.entity(camera).despawn_recursive()
+fn scene_teardown(mut cmds: Commands, entities: Query<(Entity, &ModeEntity)>) {
+    for (entity, ModeEntity(mode)) in entities.iter() {
+        if *mode == Mode::Sphere {
+            cmds.entity(entity).despawn_recursive()
+        }
     }
+}
+
+fn start_undulation(
+    mut cmd: Commands,
+    mut rng: ResMut<WallpaperRng>,
+    selected: Res<SelectedNoise>,
+) {
+    cmd.insert_resource(Undulation {
+        seed: rng.random(),
+        noise: selected.0.clone(),
+        fading: None,
+    });
+}
 
-    for light in lights.iter() {
-        cmds.entity(light).despawn_recursive()
+/// switches noise when the config's choice of noise changes. noise picked with bg-cli survives
+/// unrelated config edits. also runs as the sphere is set up, so it starts with the config's
+/// noise whenever that happens.
+fn select_noise(
+    config: Res<Config>,
+    mut selected: ResMut<SelectedNoise>,
+    mut from_config: ResMut<NoiseFromConfig>,
+) {
+    if from_config.0.as_ref() != Some(&config.sphere.noise) {
+        selected.set_if_neq(SelectedNoise(config.sphere.noise.clone()));
+        from_config.0 = Some(config.sphere.noise.clone());
     }
 }
 
-fn gen_perlin(mut cmd: Commands) {
-    cmd.insert_resource(PerlinWrapper(Perlin::new(rand::rng().random())));
+/// fades from the surface as it is over to the selected noise, instead of the surface jumping.
+fn crossfade_noise(
+    selected: Res<SelectedNoise>,
+    time: Res<Time>,
+    mut undulation: ResMut<Undulation>,
+) {
+    if selected.0 != undulation.noise {
+        debug!("fading over to {:?}", selected.0);
+        undulation.fade_to(selected.0.clone());
+    }
+
+    if undulation.fading.is_some() {
+        undulation.fade(time.delta_secs() / CROSSFADE_SECS);
+    }
 }
 
 fn add_sphere(
@@ -113,6 +278,8 @@ fn add_sphere(
     mut meshes: ResMut<Assets<Mesh>>,
     debug_material: Single<&DebugTexture>,
     mut materials: ResMut<Assets<StandardMaterial>>,
+    palette: Res<Palette>,
+    quality: Res<Quality>,
 ) {
     // let mut sphere = |mul| meshes.add(Sphere::default());
     let mut sphere = |mul| {
@@ -123,24 +290,18 @@ fn add_sphere(
         })
     };
 
-    cmds.insert_resource(Zoom::default());
+    cmds.insert_resource(LoopPhase::default());
 
     cmds.spawn((
-        Mesh3d(sphere(1.0)),
-        Transform::from_xyz(0.0, 0.0, 0.0),
-        NoWireframe,
-        BaseSphere,
-    ));
-    cmds.spawn((
-        Mesh3d(sphere(1.25)),
+        Mesh3d(sphere(BOUNDING_RADIUS)),
         Transform::from_xyz(0.0, 0.0, 0.0),
         MeshMaterial3d(
-            materials.add(StandardMaterial::from_color(Color::Srgba(Srgba::rgba_u8(
-                // 32, 96, 127, 16,
-                // 166, 227, 161, 8,
-                // 243, 139, 168, 8,
-                147, 153, 178, 8,
-            )))),
+            // Srgba::rgba_u8(32, 96, 127, 16)
+            // Srgba::rgba_u8(166, 227, 161, 8)
+            // Srgba::rgba_u8(243, 139, 168, 8)
+            materials.add(StandardMaterial::from_color(
+                palette.overlay.with_alpha(BOUNDING_ALPHA),
+            )),
         ),
         // NoWireframe,
         WireframeColor {
@@ -150,108 +311,339 @@ fn add_sphere(
             // color: Color::Srgba(Srgba::rgba_u8(17, 17, 27, 32)),
             // color: Color::Srgba(Srgba::rgba_u8(116, 199, 236, 32)),
             // color: Color::Srgba(Srgba::rgba_u8(203, 166, 247, 32)),
-            color: Color::Srgba(Srgba::rgba_u8(24, 24, 37, 32)),
+            color: palette.mantle.with_alpha(BOUNDING_WIREFRAME_ALPHA).into(),
+        },
+        Rotatable {
+            speed: ROTATION_SPEED,
         },
-        Rotatable { speed: 0.03125 },
         BoundingSphere,
+        in_mode(Mode::Sphere),
     ));
+    let (undulating, base_positions) = undulating_mesh(quality.subdivisions);
+
     cmds.spawn((
-        Mesh3d(sphere(1.0)),
+        Mesh3d(meshes.add(undulating)),
         Transform::from_xyz(0.0, 0.0, 0.0),
         // MeshMaterial3d(debug_material.0.clone()),
         MeshMaterial3d(
-            materials.add(StandardMaterial::from_color(Color::Srgba(Srgba::rgba_u8(
-                // 116, 199, 236, 64,
-                // 166, 227, 161, 64,
-                // 203, 166, 247, 64,
-                // 166, 227, 161, 64,
-                // 243, 139, 168, 127,
-                250, 179, 135, 255,
-            )))),
+            // Srgba::rgba_u8(116, 199, 236, 64)
+            // Srgba::rgba_u8(166, 227, 161, 64)
+            // Srgba::rgba_u8(203, 166, 247, 64)
+            // Srgba::rgba_u8(243, 139, 168, 127)
+            materials.add(StandardMaterial::from_color(palette.accent)),
         ),
         NoWireframe,
-        Rotatable { speed: 0.03125 },
+        Rotatable {
+            speed: ROTATION_SPEED,
+        },
         UndulateSphere,
+        base_positions,
+        Keyframes::default(),
+        in_mode(Mode::Sphere),
     ));
 }
 
+/// a sphere divided `subdivisions` times, and its vertex positions.
+fn undulating_mesh(subdivisions: u32) -> (Mesh, BasePositions) {
+    let mesh = Sphere::default()
+        .mesh()
+        .ico(subdivisions)
+        .unwrap_or_else(|e| {
+            warn!("{e}, using the default sphere");
+            Sphere::default().into()
+        });
+    let positions = match mesh.attribute(Mesh::ATTRIBUTE_POSITION) {
+        Some(VertexAttributeValues::Float32x3(positions)) => {
+            positions.iter().copied().map(Vec3::from).collect()
+        }
+        _ => Vec::new(),
+    };
+
+    (mesh, BasePositions(positions))
+}
+
+/// rebuilds the undulating sphere when the quality preset asks for a different subdivision.
+fn resize_sphere(
+    mut sphere: Single<(&Mesh3d, &mut BasePositions), With<UndulateSphere>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    quality: Res<Quality>,
+) {
+    let (mesh, base) = &mut *sphere;
+    let (resized, positions) = undulating_mesh(quality.subdivisions);
+
+    if positions.0.len() == base.0.len() {
+        return;
+    }
+
+    debug!(
+        "sphere has {} vertices instead of {}",
+        positions.0.len(),
+        base.0.len()
+    );
+    meshes.insert(mesh.id(), resized);
+    **base = positions;
+}
+
+/// recolors the spheres when the palette changes.
+fn apply_sphere_colors(
+    palette: Res<Palette>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut bounding_spheres: Query<
+        (&MeshMaterial3d<StandardMaterial>, &mut WireframeColor),
+        With<BoundingSphere>,
+    >,
+    undulate_spheres: Query<&MeshMaterial3d<StandardMaterial>, With<UndulateSphere>>,
+) {
+    for (material, mut wireframe) in &mut bounding_spheres {
+        if let Some(material) = materials.get_mut(material.id()) {
+            *material = StandardMaterial::from_color(palette.overlay.with_alpha(BOUNDING_ALPHA));
+        }
+
+        wireframe.color = palette.mantle.with_alpha(BOUNDING_WIREFRAME_ALPHA).into();
+    }
+
+    for material in &undulate_spheres {
+        if let Some(material) = materials.get_mut(material.id()) {
+            *material = StandardMaterial::from_color(palette.accent);
+        }
+    }
+}
+
+/// moves the sphere's surface along its loop by the frame's (virtual) time.
+pub(crate) fn advance_phase(time: Res<Time>, config: Res<Config>, mut phase: ResMut<LoopPhase>) {
+    // kept within one loop, so it never grows large enough to lose precision.
+    phase.0 = (phase.0 + time.delta_secs() / config.sphere.period).fract();
+}
+
 fn undulate_sphere(
-    mut sphere: Single<&Mesh3d, With<UndulateSphere>>,
-    mut base_sphere: Single<&Mesh3d, With<BaseSphere>>,
+    sphere: Single<(&Mesh3d, Ref<BasePositions>, &mut Keyframes), With<UndulateSphere>>,
+    mut timer: Single<&mut UndulateTimer>,
     mut meshes: ResMut<Assets<Mesh>>,
-    mut noise: ResMut<PerlinWrapper>,
+    undulation: Res<Undulation>,
     time: Res<Time>,
-    mut zoom: ResMut<Zoom>,
-    // mut timer: Single<&mut UndulateTimer>,
+    config: Res<Config>,
+    phase: Res<LoopPhase>,
 ) {
-    // if timer.tick(time.delta()).just_finished() {
-    // for ref mut point in sphere.0.
-    let Some(base_positions) = meshes.get(base_sphere.0.id()).map(|mesh| {
-        mesh.attribute(Mesh::ATTRIBUTE_POSITION)
-            .unwrap()
-            .as_float3()
-            .unwrap()
-            .to_vec()
-    }) else {
+    let (sphere, base, mut keyframes) = sphere.into_inner();
+    let settings = &config.sphere;
+    let td = time.delta().as_secs_f32();
+    let interval = Duration::from_secs_f32(1.0 / settings.update_rate);
+
+    if timer.duration() != interval {
+        timer.set_duration(interval);
+    }
+
+    let ticked = timer.tick(time.delta()).just_finished();
+    // a resized sphere has to be worked out again whatever the policy.
+    let resized = base.is_changed();
+    let skip = match settings.update {
+        SphereUpdate::EveryFrame => td == 0.0 && !resized && !undulation.is_changed(),
+        SphereUpdate::Interpolated => td == 0.0 && !resized && !ticked,
+        SphereUpdate::Stepped => !resized && !ticked,
+    };
+
+    // looking the mesh up mutably marks it changed, so that waits until it's going to be.
+    if skip {
+        return;
+    }
+
+    let Some(mesh) = meshes.get_mut(sphere.id()) else {
         return;
     };
 
-    let Some(mesh) = meshes.get_mut(sphere.0.id()) else {
+    // the buffers are taken out and put back so the mesh keeps its allocations.
+    let (
+        Some(VertexAttributeValues::Float32x3(mut positions)),
+        Some(VertexAttributeValues::Float32x3(mut normals)),
+    ) = (
+        mesh.remove_attribute(Mesh::ATTRIBUTE_POSITION),
+        mesh.remove_attribute(Mesh::ATTRIBUTE_NORMAL),
+    )
+    else {
+        warn!("the undulating sphere has no positions or normals");
         return;
     };
 
-    // Get the current mesh data.
-    let positions: Vec<[f32; 3]> = mesh
-            .attribute_mut(Mesh::ATTRIBUTE_POSITION)
-            .unwrap()
-            .as_float3()
-            .unwrap()
-            .to_vec()
-            // .as_typed()
-            // .into()
-        ;
-    // let base_positions: Vec<[f32; 3]> = base_mesh
-    //     .attribute_mut(Mesh::ATTRIBUTE_POSITION)
-    //     .unwrap()
-    //     .as_float3()
-    //     .unwrap()
-    //     .to_vec()
-    // .as_typed()
-    // .into()
-
-    // Modify the positions. For example, scale the mesh:
-    // let scale_factor = 2.0;
-    let mut new_positions: Vec<[f32; 3]> = Vec::with_capacity(positions.len());
-    let td = time.delta().as_secs_f32();
-    zoom.0 += 0.5 * TAU * td;
-
-    for pos in base_positions.iter() {
-        let scale_factor = noise.0.get([
-            (pos[0] * zoom.0) as f64,
-            (pos[1] * zoom.0) as f64,
-            (pos[2] * zoom.0) as f64,
-        ]) as f32
-            * 0.25;
-
-        new_positions.push([
-            pos[0] + pos[0] * scale_factor,
-            pos[1] + pos[1] * scale_factor,
-            pos[2] + pos[2] * scale_factor,
-        ]);
+    let shape = |phase: f32, positions: &mut [[f32; 3]], normals: &mut [[f32; 3]]| {
+        undulate_parallel(
+            ComputeTaskPool::get(),
+            &undulation,
+            loop_offset(phase, settings.period),
+            &base.0,
+            positions,
+            normals,
+        );
+    };
+
+    match settings.update {
+        SphereUpdate::EveryFrame | SphereUpdate::Stepped => {
+            shape(phase.0, &mut positions, &mut normals)
+        }
+        SphereUpdate::Interpolated => {
+            // how far the loop gets between updates.
+            let step = 1.0 / (settings.update_rate * settings.period);
+            let Keyframes { last, next } = &mut *keyframes;
+            let fresh = resized || next.positions.len() != base.0.len();
+
+            if fresh {
+                for keyframe in [&mut *last, &mut *next] {
+                    keyframe.positions.resize(base.0.len(), [0.0; 3]);
+                    keyframe.normals.resize(base.0.len(), [0.0; 3]);
+                }
+
+                shape(phase.0, &mut last.positions, &mut last.normals);
+            } else if ticked {
+                mem::swap(last, next);
+            }
+
+            if fresh || ticked {
+                shape(
+                    (phase.0 + step).fract(),
+                    &mut next.positions,
+                    &mut next.normals,
+                );
+            }
+
+            keyframes.blend(timer.fraction(), &mut positions, &mut normals);
+        }
+    }
+
+    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, normals);
+}
+
+impl Undulation {
+    /// starts fading over to `noise` from the surface as it is.
+    fn fade_to(&mut self, noise: SphereNoise) {
+        let to = Self {
+            seed: self.seed,
+            noise,
+            fading: None,
+        };
+        let from = mem::replace(self, to);
+        self.fading = Some((Box::new(from), 0.0));
+    }
+
+    /// moves every fade along by `step`, dropping the ones that are done.
+    fn fade(&mut self, step: f32) {
+        let Some((from, fade)) = &mut self.fading else {
+            return;
+        };
+
+        from.fade(step);
+        *fade += step;
+
+        if *fade >= 1.0 {
+            self.fading = None;
+        }
     }
+}
 
-    // Update the mesh with the modified positions.
-    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, new_positions);
+/// samples an `Undulation`'s noise, blended with everything it's fading from.
+struct Blend<'a> {
+    to: Sampler<'a>,
+    from: Option<(Box<Blend<'a>>, f32)>,
+}
 
-    // info!("undulated");
+impl<'a> Blend<'a> {
+    fn new(undulation: &'a Undulation) -> Self {
+        Self {
+            to: Sampler::new(&undulation.noise, undulation.seed),
+            from: undulation.fading.as_ref().map(|(from, fade)| {
+                // eased, so the fade doesn't start or stop with a jolt.
+                (Box::new(Self::new(from)), fade * fade * (3.0 - 2.0 * fade))
+            }),
+        }
+    }
 
-    // Optionally, re-compute normals after modifying positions.
-    // mesh.compute_flat_normals(); // Or mesh.compute_smooth_normals();
-    mesh.compute_smooth_normals();
-    // mesh.verte
+    fn height(&self, pos: Vec3, offset: Vec4) -> (f32, Vec3) {
+        let (height, slope) = self.to.height(pos, offset);
 
-    // You can also add or remove attributes, change indices, etc. as needed.
-    // }
+        match &self.from {
+            Some((from, fade)) => {
+                let (from_height, from_slope) = from.height(pos, offset);
+                (
+                    from_height + (height - from_height) * fade,
+                    from_slope.lerp(slope, *fade),
+                )
+            }
+            None => (height, slope),
+        }
+    }
+}
+
+impl Keyframes {
+    /// the surface `t` of the way from the last keyframe to the next.
+    fn blend(&self, t: f32, positions: &mut [[f32; 3]], normals: &mut [[f32; 3]]) {
+        let last = self.last.positions.iter().zip(&self.last.normals);
+        let next = self.next.positions.iter().zip(&self.next.normals);
+
+        for (((last, next), position), normal) in last.zip(next).zip(positions).zip(normals) {
+            *position = Vec3::from(*last.0).lerp(Vec3::from(*next.0), t).to_array();
+            *normal = Vec3::from(*last.1)
+                .lerp(Vec3::from(*next.1), t)
+                .normalize_or_zero()
+                .to_array();
+        }
+    }
+}
+
+/// where in 4D noise the sphere's center is `phase` of the way through the loop. it goes round a
+/// circle that leans into the fourth dimension, so the surface drifts along and comes back to
+/// where it started every `period` seconds.
+pub fn loop_offset(phase: f32, period: f32) -> Vec4 {
+    let radius = DRIFT * period / TAU;
+    let (sin, cos) = (phase * TAU).sin_cos();
+
+    (DRIFT_AXIS * cos * radius).extend(sin * radius)
+}
+
+/// moves each of the `base` positions in or out along its direction from the center by the noise
+/// at `offset` from it, writing the moved positions and their normals.
+pub fn undulate(
+    undulation: &Undulation,
+    offset: Vec4,
+    base: &[Vec3],
+    positions: &mut [[f32; 3]],
+    normals: &mut [[f32; 3]],
+) {
+    let surface = Blend::new(undulation);
+
+    for ((pos, position), normal) in base.iter().zip(positions).zip(normals) {
+        let (height, slope) = surface.height(*pos, offset);
+
+        // the surface is `height * dir` over the directions `dir`, its normal leans away from
+        // `dir` by however steeply the height changes across the surface.
+        let dir = pos.normalize_or_zero();
+        let slope = slope * pos.length();
+        let across = slope - slope.dot(dir) * dir;
+
+        *position = (*pos * height).to_array();
+        *normal = (dir * height - across).normalize_or(dir).to_array();
+    }
+}
+
+/// `undulate`, split into chunks across the threads of `pool`. each vertex is worked out on its
+/// own, so the result is the same as doing them all on one thread.
+pub fn undulate_parallel(
+    pool: &TaskPool,
+    undulation: &Undulation,
+    offset: Vec4,
+    base: &[Vec3],
+    positions: &mut [[f32; 3]],
+    normals: &mut [[f32; 3]],
+) {
+    let chunk = base.len().div_ceil(pool.thread_num()).max(MIN_CHUNK);
+
+    pool.scope(|scope| {
+        for ((base, positions), normals) in base
+            .chunks(chunk)
+            .zip(positions.chunks_mut(chunk))
+            .zip(normals.chunks_mut(chunk))
+        {
+            scope.spawn(async move { undulate(undulation, offset, base, positions, normals) });
+        }
+    });
 }
 
 // This system will rotate any entity in the scene with a Rotatable component around its y-axis.
@@ -263,3 +655,216 @@ fn rotate_sphere(mut spheres: Query<(&mut Transform, &Rotatable)>, timer: Res<Ti
         transform.rotate_y(sphere.speed * TAU * timer.delta_secs());
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{Fractal, NoiseKind};
+    use bevy::{tasks::TaskPoolBuilder, time::TimeUpdateStrategy};
+
+    #[test]
+    fn low_power_frame_rate_follows_the_motion() {
+        let settings = SphereConfig::default();
+        let noise = SphereNoise::default();
+        let fps = camera_view(&settings, &noise).fps;
+
+        // the turning sets the pace with the default noise.
+        assert!((20.0..25.0).contains(&fps), "{fps}");
+
+        let wild = SphereNoise {
+            amplitude: noise.amplitude * 4.0,
+            ..noise.clone()
+        };
+
+        assert!(camera_view(&settings, &wild).fps > fps * 2.0);
+
+        let stepped = SphereConfig {
+            update: SphereUpdate::Stepped,
+            ..settings
+        };
+
+        assert_eq!(camera_view(&stepped, &wild).fps, fps);
+    }
+
+    #[test]
+    fn the_sphere_starts_with_the_configs_noise() {
+        let mut world = World::new();
+        let mut config = Config::default();
+        config.sphere.noise.kind = NoiseKind::Worley;
+
+        world.insert_resource(config);
+        world.init_resource::<SelectedNoise>();
+        world.init_resource::<NoiseFromConfig>();
+        world.init_resource::<WallpaperRng>();
+        Schedule::default()
+            .add_systems((select_noise, start_undulation).chain())
+            .run(&mut world);
+
+        assert_eq!(world.resource::<Undulation>().noise.kind, NoiseKind::Worley);
+    }
+
+    #[test]
+    fn changing_noise_mid_fade_fades_from_where_it_was() {
+        let noise = |kind| SphereNoise { kind, ..default() };
+        let mut undulation = Undulation {
+            seed: 7,
+            noise: noise(NoiseKind::Perlin),
+            fading: None,
+        };
+        let at = |undulation: &Undulation| {
+            let (_, base) = undulating_mesh(3);
+            let surface = Blend::new(undulation);
+            let offset = loop_offset(0.3, 60.0);
+
+            base.0
+                .iter()
+                .map(|pos| surface.height(*pos, offset).0)
+                .collect::<Vec<_>>()
+        };
+
+        undulation.fade_to(noise(NoiseKind::Value));
+        undulation.fade(0.5);
+        let before = at(&undulation);
+        undulation.fade_to(noise(NoiseKind::Simplex));
+
+        assert_eq!(at(&undulation), before);
+
+        // the earlier fade carries on underneath, and everything is gone once the last is done.
+        undulation.fade(0.6);
+        assert!(undulation.fading.as_ref().unwrap().0.fading.is_none());
+        undulation.fade(0.4);
+        assert!(undulation.fading.is_none());
+    }
+
+    /// how many times the sphere's mesh changes and how many keyframes are worked out over a
+    /// second at 60 fps.
+    fn rebuilds(update: SphereUpdate) -> (usize, usize) {
+        #[derive(Resource, Default)]
+        struct Rebuilds {
+            meshes: usize,
+            keyframes: usize,
+        }
+
+        fn count(
+            mut events: EventReader<AssetEvent<Mesh>>,
+            keyframes: Single<&Keyframes>,
+            mut next: Local<Option<[f32; 3]>>,
+            mut rebuilds: ResMut<Rebuilds>,
+        ) {
+            for event in events.read() {
+                if let AssetEvent::Modified { .. } = event {
+                    rebuilds.meshes += 1;
+                }
+            }
+
+            let latest = keyframes.next.positions.first().copied();
+
+            if latest != *next {
+                *next = latest;
+                rebuilds.keyframes += 1;
+            }
+        }
+
+        let mut app = App::new();
+        let mut config = Config::default();
+        config.sphere.update = update;
+
+        app.add_plugins((MinimalPlugins, AssetPlugin::default()))
+            .init_asset::<Mesh>()
+            .insert_resource(config)
+            .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f32(
+                1.0 / 60.0,
+            )))
+            .insert_resource(Undulation {
+                seed: 7,
+                noise: default(),
+                fading: None,
+            })
+            .init_resource::<LoopPhase>()
+            .init_resource::<Rebuilds>()
+            .add_systems(Update, (advance_phase, undulate_sphere).chain())
+            .add_systems(Last, count);
+
+        let (mesh, base) = undulating_mesh(3);
+        let mesh = app.world_mut().resource_mut::<Assets<Mesh>>().add(mesh);
+        app.world_mut()
+            .spawn((Mesh3d(mesh), UndulateSphere, base, Keyframes::default()));
+        app.world_mut().spawn(UndulateTimer(Timer::from_seconds(
+            0.5,
+            TimerMode::Repeating,
+        )));
+
+        // the first frame sets everything up.
+        app.update();
+        *app.world_mut().resource_mut::<Rebuilds>() = Rebuilds::default();
+
+        for _ in 0..60 {
+            app.update();
+        }
+
+        let rebuilds = app.world().resource::<Rebuilds>();
+        (rebuilds.meshes, rebuilds.keyframes)
+    }
+
+    #[test]
+    fn meshes_are_rebuilt_as_often_as_the_policy_says() {
+        let rate = SphereConfig::default().update_rate as usize;
+
+        assert_eq!(rebuilds(SphereUpdate::EveryFrame), (60, 0));
+        assert_eq!(rebuilds(SphereUpdate::Stepped), (rate, 0));
+        assert_eq!(rebuilds(SphereUpdate::Interpolated), (60, rate));
+    }
+
+    #[test]
+    fn chunked_undulation_matches_one_thread() {
+        let (_, base) = undulating_mesh(20);
+        let pool = TaskPoolBuilder::new().num_threads(4).build();
+        let bits = |v: &[[f32; 3]]| v.iter().flatten().map(|f| f.to_bits()).collect::<Vec<_>>();
+        let fbm = SphereNoise {
+            kind: NoiseKind::Simplex,
+            fractal: Fractal::Fbm,
+            ..default()
+        };
+        let undulations = [
+            Undulation {
+                seed: 7,
+                noise: SphereNoise::default(),
+                fading: None,
+            },
+            Undulation {
+                seed: 7,
+                noise: SphereNoise::default(),
+                fading: Some((
+                    Box::new(Undulation {
+                        seed: 7,
+                        noise: fbm,
+                        fading: None,
+                    }),
+                    0.4,
+                )),
+            },
+        ];
+
+        // enough vertices for a few chunks on every thread.
+        assert!(base.0.len() > 4 * MIN_CHUNK);
+
+        for undulation in &undulations {
+            let offset = loop_offset(0.3, 60.0);
+            let mut serial = (vec![[0.0; 3]; base.0.len()], vec![[0.0; 3]; base.0.len()]);
+            let mut parallel = serial.clone();
+
+            undulate(undulation, offset, &base.0, &mut serial.0, &mut serial.1);
+            undulate_parallel(
+                &pool,
+                undulation,
+                offset,
+                &base.0,
+                &mut parallel.0,
+                &mut parallel.1,
+            );
+
+            assert_eq!(bits(&serial.0), bits(&parallel.0));
+            assert_eq!(bits(&serial.1), bits(&parallel.1));
+        }
+    }
+}
@@ -0,0 +1,143 @@
+use super::gradient;
+use crate::config::{Fractal, NoiseKind, SphereNoise};
+use bevy::math::{DVec4, Vec3, Vec4};
+use noise::{
+    Billow, Fbm, MultiFractal, NoiseFn, OpenSimplex, Perlin, RidgedMulti, Seedable, Simplex,
+    Turbulence, Value, Worley, core::worley::ReturnType,
+};
+use std::{cell::RefCell, rc::Rc};
+
+/// how far apart samples are taken to estimate the gradient of noise that can't give its own.
+const STEP: f64 = 1e-3;
+/// how many built noises each thread holds on to, enough for a few fades on top of each other.
+const KEPT: usize = 4;
+
+thread_local! {
+    /// the noise built on this thread, oldest first, with the seed it was built with.
+    static BUILT: RefCell<Vec<(SphereNoise, u32, Rc<Source>)>> = const { RefCell::new(Vec::new()) };
+}
+
+/// samples a `SphereNoise`. some of the noise crate's functions can't be sent between threads,
+/// so each thread builds its own, once for as long as the noise is in use.
+pub struct Sampler<'a> {
+    noise: &'a SphereNoise,
+    source: Rc<Source>,
+}
+
+enum Source {
+    /// plain perlin noise, which works out its gradient exactly.
+    Gradient(Box<gradient::Perlin>),
+    Sampled(Box<dyn NoiseFn<f64, 4>>),
+}
+
+impl<'a> Sampler<'a> {
+    pub fn new(noise: &'a SphereNoise, seed: u32) -> Self {
+        let source = BUILT.with_borrow_mut(|built| {
+            let kept = built.iter().find(|(n, s, _)| n == noise && *s == seed);
+
+            if let Some((_, _, source)) = kept {
+                return source.clone();
+            }
+
+            let source = Rc::new(Source::new(noise, seed));
+
+            if built.len() == KEPT {
+                built.remove(0);
+            }
+
+            built.push((noise.clone(), seed, source.clone()));
+            source
+        });
+
+        Self { noise, source }
+    }
+
+    /// how far from the center the surface is in the direction of `pos` from it, with the sphere's
+    /// center at `offset` in the noise, and how fast that changes around `pos`.
+    pub fn height(&self, pos: Vec3, offset: Vec4) -> (f32, Vec3) {
+        let point = (pos * self.noise.frequency).extend(0.0) + offset;
+        let (value, gradient) = match &*self.source {
+            Source::Gradient(perlin) => {
+                let (value, gradient) = perlin.sample(point.to_array());
+                (value, Vec4::from(gradient).truncate())
+            }
+            Source::Sampled(noise) => sampled(noise, point),
+        };
+
+        (
+            1.0 + value * self.noise.amplitude,
+            gradient * self.noise.amplitude * self.noise.frequency,
+        )
+    }
+}
+
+impl Source {
+    fn new(noise: &SphereNoise, seed: u32) -> Self {
+        if noise.kind == NoiseKind::Perlin && noise.fractal == Fractal::None && noise.warp == 0.0 {
+            Self::Gradient(Box::new(gradient::Perlin::new(seed)))
+        } else {
+            Self::Sampled(build(noise, seed))
+        }
+    }
+}
+
+/// the noise at `point` and its gradient over the three spatial dimensions, from central
+/// differences.
+fn sampled(noise: &dyn NoiseFn<f64, 4>, point: Vec4) -> (f32, Vec3) {
+    let point = point.as_dvec4();
+    let at = |delta: DVec4| noise.get((point + delta).to_array());
+    let mut gradient = Vec3::ZERO;
+
+    for d in 0..3 {
+        let mut delta = DVec4::ZERO;
+        delta[d] = STEP;
+        gradient[d] = ((at(delta) - at(-delta)) / (2.0 * STEP)) as f32;
+    }
+
+    (at(DVec4::ZERO) as f32, gradient)
+}
+
+fn build(noise: &SphereNoise, seed: u32) -> Box<dyn NoiseFn<f64, 4>> {
+    let source = match noise.kind {
+        NoiseKind::Perlin => fractal::<Perlin>(noise, seed),
+        NoiseKind::OpenSimplex => fractal::<OpenSimplex>(noise, seed),
+        NoiseKind::Simplex => fractal::<Simplex>(noise, seed),
+        NoiseKind::Value => fractal::<Value>(noise, seed),
+        // on its own, the distance to the nearest point gives rounded cells instead of flat ones.
+        NoiseKind::Worley if noise.fractal == Fractal::None => {
+            Box::new(Worley::new(seed).set_return_type(ReturnType::Distance))
+        }
+        NoiseKind::Worley => fractal::<Worley>(noise, seed),
+    };
+
+    if noise.warp == 0.0 {
+        return source;
+    }
+
+    Box::new(
+        Turbulence::<_, Perlin>::new(source)
+            .set_seed(seed.wrapping_add(1))
+            .set_power(noise.warp.into()),
+    )
+}
+
+fn fractal<T>(noise: &SphereNoise, seed: u32) -> Box<dyn NoiseFn<f64, 4>>
+where
+    T: Default + Seedable + NoiseFn<f64, 4> + 'static,
+{
+    /// the frequency is applied before sampling, the same as for a single octave.
+    fn octaves<F: MultiFractal>(fractal: F, noise: &SphereNoise) -> F {
+        fractal
+            .set_octaves(noise.octaves)
+            .set_frequency(1.0)
+            .set_lacunarity(noise.lacunarity.into())
+            .set_persistence(noise.persistence.into())
+    }
+
+    match noise.fractal {
+        Fractal::None => Box::new(T::default().set_seed(seed)),
+        Fractal::Fbm => Box::new(octaves(Fbm::<T>::new(seed), noise)),
+        Fractal::Ridged => Box::new(octaves(RidgedMulti::<T>::new(seed), noise)),
+        Fractal::Billow => Box::new(octaves(Billow::<T>::new(seed), noise)),
+    }
+}